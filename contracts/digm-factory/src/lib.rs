@@ -0,0 +1,397 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult,
+    SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw_controllers::{Admin, AdminError};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_utils::{parse_reply_instantiate_data, ParseReplyError};
+use digm_nft::{
+    AlbumMetadata, CurveType, GovernanceConfig, Payee, PlatformFee, PricingConfig, Royalty, SupplyConfig,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const CONTRACT_NAME: &str = "crates.io:digm-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const BPS_DENOMINATOR: u64 = 10_000;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+const INSTANTIATE_COLLECTION_REPLY_ID: u64 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    // Defaults to the instantiator
+    pub admin: Option<String>,
+    pub nft_code_id: u64,
+    // Every collection created here pays this cut of its curve revenue
+    pub platform_fee_recipient: String,
+    pub platform_fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    // Launch a digm-nft collection administered by the sender
+    CreateCollection(CreateCollectionMsg),
+    // Admin controls, changes only apply to collections created afterwards
+    UpdateConfig {
+        nft_code_id: Option<u64>,
+        platform_fee_recipient: Option<String>,
+        platform_fee_bps: Option<u64>,
+    },
+    TransferAdmin { new_admin: String },
+}
+
+// Artist-supplied digm-nft parameters, the factory fills in admin, minter and platform fee
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreateCollectionMsg {
+    pub name: String,
+    pub symbol: String,
+    pub curve_start_price: Uint128,
+    pub curve_rate: Uint128,
+    pub curve_type: Option<CurveType>,
+    pub curve_denom: String,
+    pub sell_spread_bps: Option<u64>,
+    pub supply: Option<SupplyConfig>,
+    pub royalty: Option<Royalty>,
+    pub payees: Option<Vec<Payee>>,
+    pub base_uri: Option<String>,
+    pub album: Option<AlbumMetadata>,
+    pub governance: Option<GovernanceConfig>,
+    pub pricing: Option<PricingConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetConfig {},
+    GetAdmin {},
+    CollectionsByArtist { artist: String, start_after: Option<String>, limit: Option<u32> },
+    AllCollections { start_after: Option<String>, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub nft_code_id: u64,
+    pub platform_fee_recipient: Addr,
+    pub platform_fee_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Collection {
+    pub address: Addr,
+    pub artist: Addr,
+    pub name: String,
+    pub symbol: String,
+    pub created_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionsResponse {
+    pub collections: Vec<Collection>,
+}
+
+// Collection being instantiated, completed in reply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingCollection {
+    pub artist: Addr,
+    pub name: String,
+    pub symbol: String,
+}
+
+pub struct CollectionIndexes<'a> {
+    pub artist: MultiIndex<'a, Addr, Collection, Addr>,
+}
+
+impl<'a> IndexList<Collection> for CollectionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Collection>> + '_> {
+        let v: Vec<&dyn Index<Collection>> = vec![&self.artist];
+        Box::new(v.into_iter())
+    }
+}
+
+// Collection address -> collection, indexed by artist
+pub fn collections<'a>() -> IndexedMap<'a, &'a Addr, Collection, CollectionIndexes<'a>> {
+    let indexes = CollectionIndexes {
+        artist: MultiIndex::new(
+            |_pk, collection| collection.artist.clone(),
+            "collections",
+            "collections__artist",
+        ),
+    };
+    IndexedMap::new("collections", indexes)
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const ADMIN: Admin = Admin::new("admin");
+pub const PENDING_COLLECTION: Item<PendingCollection> = Item::new("pending_collection");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        nft_code_id: msg.nft_code_id,
+        platform_fee_recipient: deps.api.addr_validate(&msg.platform_fee_recipient)?,
+        platform_fee_bps: msg.platform_fee_bps,
+    };
+    validate_platform_fee(config.platform_fee_bps)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+    ADMIN.set(deps.branch(), Some(admin))?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("nft_code_id", config.nft_code_id.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateCollection(collection) => execute_create_collection(deps, info, collection),
+        ExecuteMsg::UpdateConfig { nft_code_id, platform_fee_recipient, platform_fee_bps } => {
+            execute_update_config(deps, info, nft_code_id, platform_fee_recipient, platform_fee_bps)
+        }
+        ExecuteMsg::TransferAdmin { new_admin } => {
+            let new_admin = deps.api.addr_validate(&new_admin)?;
+            Ok(ADMIN.execute_update_admin(deps, info, Some(new_admin))?)
+        }
+    }
+}
+
+pub fn execute_create_collection(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection: CreateCollectionMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let artist = info.sender;
+    if let Some(supply) = &collection.supply {
+        validate_supply(supply)?;
+    }
+
+    let nft_msg = digm_nft::InstantiateMsg {
+        name: collection.name.clone(),
+        symbol: collection.symbol.clone(),
+        minter: artist.to_string(),
+        admin: Some(artist.to_string()),
+        curve_start_price: collection.curve_start_price,
+        curve_rate: collection.curve_rate,
+        curve_type: collection.curve_type,
+        curve_denom: collection.curve_denom,
+        sell_spread_bps: collection.sell_spread_bps,
+        contribution_root: None,
+        royalty: collection.royalty,
+        payees: collection.payees,
+        base_uri: collection.base_uri,
+        phases: None,
+        sale_mode: None,
+        album: collection.album,
+        soulbound_contributions: None,
+        supply: collection.supply,
+        platform_fee: Some(PlatformFee {
+            recipient: config.platform_fee_recipient.to_string(),
+            bps: config.platform_fee_bps,
+        }),
+        governance: collection.governance,
+        pricing: collection.pricing,
+    };
+
+    PENDING_COLLECTION.save(deps.storage, &PendingCollection {
+        artist: artist.clone(),
+        name: collection.name,
+        symbol: collection.symbol.clone(),
+    })?;
+
+    // Contract upgrades stay with the factory admin, the artist administers the collection itself
+    let instantiate = WasmMsg::Instantiate {
+        admin: ADMIN.get(deps.as_ref())?.map(|admin| admin.to_string()),
+        code_id: config.nft_code_id,
+        msg: to_binary(&nft_msg)?,
+        funds: vec![],
+        label: format!("digm-nft {} by {}", collection.symbol, artist),
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(instantiate, INSTANTIATE_COLLECTION_REPLY_ID))
+        .add_attribute("method", "create_collection")
+        .add_attribute("artist", artist)
+        .add_attribute("code_id", config.nft_code_id.to_string()))
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    nft_code_id: Option<u64>,
+    platform_fee_recipient: Option<String>,
+    platform_fee_bps: Option<u64>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(nft_code_id) = nft_code_id {
+        config.nft_code_id = nft_code_id;
+    }
+    if let Some(platform_fee_recipient) = platform_fee_recipient {
+        config.platform_fee_recipient = deps.api.addr_validate(&platform_fee_recipient)?;
+    }
+    if let Some(platform_fee_bps) = platform_fee_bps {
+        validate_platform_fee(platform_fee_bps)?;
+        config.platform_fee_bps = platform_fee_bps;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_config")
+        .add_attribute("nft_code_id", config.nft_code_id.to_string())
+        .add_attribute("platform_fee_bps", config.platform_fee_bps.to_string()))
+}
+
+fn validate_platform_fee(bps: u64) -> Result<(), ContractError> {
+    if bps > BPS_DENOMINATOR {
+        return Err(ContractError::InvalidPlatformFee { bps });
+    }
+    Ok(())
+}
+
+// digm-nft takes any caps, a collection launched here has to have curve tokens to sell
+fn validate_supply(supply: &SupplyConfig) -> Result<(), ContractError> {
+    if supply.curve_supply == 0 {
+        return Err(ContractError::InvalidSupply {
+            reason: "curve supply cannot be zero".to_string(),
+        });
+    }
+    if supply.curve_supply.checked_add(supply.contribution_supply).is_none() {
+        return Err(ContractError::InvalidSupply {
+            reason: "total supply overflows".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != INSTANTIATE_COLLECTION_REPLY_ID {
+        return Err(ContractError::UnknownReply { id: msg.id });
+    }
+
+    let instantiated = parse_reply_instantiate_data(msg)?;
+    let address = deps.api.addr_validate(&instantiated.contract_address)?;
+    let pending = PENDING_COLLECTION.load(deps.storage)?;
+    PENDING_COLLECTION.remove(deps.storage);
+
+    let collection = Collection {
+        address: address.clone(),
+        artist: pending.artist,
+        name: pending.name,
+        symbol: pending.symbol,
+        created_at: env.block.time,
+    };
+    collections().save(deps.storage, &address, &collection)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "collection_created")
+        .add_attribute("collection", address)
+        .add_attribute("artist", collection.artist))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetConfig {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::GetAdmin {} => to_binary(&ADMIN.query_admin(deps)?),
+        QueryMsg::CollectionsByArtist { artist, start_after, limit } => {
+            to_binary(&query_collections_by_artist(deps, artist, start_after, limit)?)
+        }
+        QueryMsg::AllCollections { start_after, limit } => {
+            to_binary(&query_all_collections(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_collections_by_artist(
+    deps: Deps,
+    artist: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    let artist = deps.api.addr_validate(&artist)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?
+        .map(Bound::exclusive);
+
+    let collections = collections()
+        .idx
+        .artist
+        .prefix(artist)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, collection)| collection))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionsResponse { collections })
+}
+
+fn query_all_collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let collections = collections()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, collection)| collection))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(CollectionsResponse { collections })
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("Invalid platform fee: {bps} bps exceeds 10000")]
+    InvalidPlatformFee { bps: u64 },
+
+    #[error("Invalid supply: {reason}")]
+    InvalidSupply { reason: String },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply { id: u64 },
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,236 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{from_binary, CosmosMsg, OwnedDeps, SubMsgResponse, SubMsgResult};
+
+use super::*;
+
+type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        admin: None,
+        nft_code_id: 7,
+        platform_fee_recipient: "treasury".to_string(),
+        platform_fee_bps: 250,
+    }
+}
+
+fn setup() -> TestDeps {
+    let mut deps = mock_dependencies();
+    instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), instantiate_msg()).unwrap();
+    deps
+}
+
+fn collection_msg(symbol: &str) -> CreateCollectionMsg {
+    CreateCollectionMsg {
+        name: "Digm".to_string(),
+        symbol: symbol.to_string(),
+        curve_start_price: Uint128::new(1_000_000),
+        curve_rate: Uint128::new(1_000),
+        curve_type: None,
+        curve_denom: "ujuno".to_string(),
+        sell_spread_bps: None,
+        supply: None,
+        royalty: None,
+        payees: None,
+        base_uri: None,
+        album: None,
+        governance: None,
+        pricing: None,
+    }
+}
+
+fn create(deps: &mut TestDeps, artist: &str, msg: CreateCollectionMsg) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(artist, &[]), ExecuteMsg::CreateCollection(msg))
+}
+
+// Protobuf MsgInstantiateContractResponse carrying only the contract address
+fn instantiate_reply(id: u64, address: &str) -> Reply {
+    let mut data = vec![0x0a, address.len() as u8];
+    data.extend_from_slice(address.as_bytes());
+    Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(Binary::from(data)),
+        }),
+    }
+}
+
+fn launch(deps: &mut TestDeps, artist: &str, symbol: &str, address: &str) {
+    create(deps, artist, collection_msg(symbol)).unwrap();
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        instantiate_reply(INSTANTIATE_COLLECTION_REPLY_ID, address),
+    )
+    .unwrap();
+}
+
+fn addresses(res: Binary) -> Vec<String> {
+    from_binary::<CollectionsResponse>(&res)
+        .unwrap()
+        .collections
+        .into_iter()
+        .map(|collection| collection.address.to_string())
+        .collect()
+}
+
+#[test]
+fn instantiate_saves_config_and_admin() {
+    let deps = setup();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap();
+    let config: Config = from_binary(&res).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            nft_code_id: 7,
+            platform_fee_recipient: Addr::unchecked("treasury"),
+            platform_fee_bps: 250,
+        }
+    );
+    assert_eq!(ADMIN.get(deps.as_ref()).unwrap(), Some(Addr::unchecked("creator")));
+
+    let mut deps = mock_dependencies();
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator", &[]),
+        InstantiateMsg { platform_fee_bps: 10_001, ..instantiate_msg() },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPlatformFee { bps: 10_001 });
+}
+
+#[test]
+fn create_collection_instantiates_digm_nft_for_the_artist() {
+    let mut deps = setup();
+    let res = create(&mut deps, "artist", collection_msg("ONE")).unwrap();
+
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].id, INSTANTIATE_COLLECTION_REPLY_ID);
+    let nft_msg: digm_nft::InstantiateMsg = match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate { admin, code_id, msg, .. }) => {
+            assert_eq!(admin.as_deref(), Some("creator"));
+            assert_eq!(*code_id, 7);
+            from_binary(msg).unwrap()
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    };
+    assert_eq!(nft_msg.admin.as_deref(), Some("artist"));
+    assert_eq!(nft_msg.minter, "artist");
+    assert_eq!(
+        nft_msg.platform_fee,
+        Some(PlatformFee {
+            recipient: "treasury".to_string(),
+            bps: 250,
+        })
+    );
+}
+
+#[test]
+fn create_collection_validates_supply() {
+    let mut deps = setup();
+
+    let err = create(
+        &mut deps,
+        "artist",
+        CreateCollectionMsg {
+            supply: Some(SupplyConfig { curve_supply: 0, contribution_supply: 10 }),
+            ..collection_msg("ONE")
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSupply { .. }));
+
+    let err = create(
+        &mut deps,
+        "artist",
+        CreateCollectionMsg {
+            supply: Some(SupplyConfig { curve_supply: u32::MAX, contribution_supply: 1 }),
+            ..collection_msg("ONE")
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSupply { .. }));
+
+    create(
+        &mut deps,
+        "artist",
+        CreateCollectionMsg {
+            supply: Some(SupplyConfig { curve_supply: 100, contribution_supply: 0 }),
+            ..collection_msg("ONE")
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn reply_records_the_pending_collection() {
+    let mut deps = setup();
+    create(&mut deps, "artist", collection_msg("ONE")).unwrap();
+
+    let err = reply(deps.as_mut(), mock_env(), instantiate_reply(2, "collection1")).unwrap_err();
+    assert_eq!(err, ContractError::UnknownReply { id: 2 });
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        instantiate_reply(INSTANTIATE_COLLECTION_REPLY_ID, "collection1"),
+    )
+    .unwrap();
+    assert_eq!(PENDING_COLLECTION.may_load(deps.as_ref().storage).unwrap(), None);
+
+    let collection = collections()
+        .load(deps.as_ref().storage, &Addr::unchecked("collection1"))
+        .unwrap();
+    assert_eq!(
+        collection,
+        Collection {
+            address: Addr::unchecked("collection1"),
+            artist: Addr::unchecked("artist"),
+            name: "Digm".to_string(),
+            symbol: "ONE".to_string(),
+            created_at: mock_env().block.time,
+        }
+    );
+}
+
+#[test]
+fn collections_are_listed_by_artist_and_in_full() {
+    let mut deps = setup();
+    launch(&mut deps, "artist", "ONE", "collection1");
+    launch(&mut deps, "other", "TWO", "collection2");
+    launch(&mut deps, "artist", "THREE", "collection3");
+
+    let by_artist = |start_after: Option<&str>| {
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::CollectionsByArtist {
+                artist: "artist".to_string(),
+                start_after: start_after.map(str::to_string),
+                limit: None,
+            },
+        )
+        .unwrap()
+    };
+    assert_eq!(addresses(by_artist(None)), vec!["collection1", "collection3"]);
+    assert_eq!(addresses(by_artist(Some("collection1"))), vec!["collection3"]);
+
+    let all = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllCollections { start_after: None, limit: Some(2) },
+    )
+    .unwrap();
+    assert_eq!(addresses(all), vec!["collection1", "collection2"]);
+
+    let rest = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllCollections { start_after: Some("collection2".to_string()), limit: None },
+    )
+    .unwrap();
+    assert_eq!(addresses(rest), vec!["collection3"]);
+}
@@ -0,0 +1,480 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Uint128, WasmMsg, coins,
+};
+use cw2::set_contract_version;
+use cw721::{Cw721ReceiveMsg, NftInfoResponse, OwnerOfResponse};
+use cw_controllers::{Admin, AdminError};
+use cw_storage_plus::{Bound, Item, Map};
+use cw_utils::{must_pay, PaymentError};
+use digm_nft::{ContractExtension, Cw2981QueryMsg, RoyaltiesInfoResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const CONTRACT_NAME: &str = "crates.io:digm-marketplace";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const BPS_DENOMINATOR: u64 = 10_000;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    // Defaults to the instantiator
+    pub admin: Option<String>,
+    // The digm-nft collection traded here
+    pub nft_contract: String,
+    // Denom listings are priced and paid in
+    pub denom: String,
+    pub platform_fee_bps: u64,
+    pub fee_recipient: String,
+    // Share of every sale paid to the album artist, on top of the royalty
+    pub artist_cut_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    // cw721 receive hook, tokens are listed by sending them with a ReceiveMsg
+    ReceiveNft(Cw721ReceiveMsg),
+    // Return a listed token to its seller
+    Delist { token_id: String },
+    // Buy a listed token, the exact price has to be attached
+    Buy { token_id: String },
+    UpdatePrice { token_id: String, price: Uint128 },
+    // Admin controls
+    UpdateConfig {
+        platform_fee_bps: Option<u64>,
+        fee_recipient: Option<String>,
+        artist_cut_bps: Option<u64>,
+    },
+    TransferAdmin { new_admin: String },
+}
+
+// Message attached to SendNft
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    List { price: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    GetConfig {},
+    GetAdmin {},
+    GetListing { token_id: String },
+    GetListings { start_after: Option<String>, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub nft_contract: Addr,
+    pub denom: String,
+    pub platform_fee_bps: u64,
+    pub fee_recipient: Addr,
+    pub artist_cut_bps: u64,
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.denom.is_empty() {
+            return Err(ContractError::InvalidConfig {
+                reason: "denom cannot be empty".to_string(),
+            });
+        }
+        // Royalties come on top, so every sale is also checked against its price
+        if self.platform_fee_bps + self.artist_cut_bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidConfig {
+                reason: "platform fee and artist cut cannot exceed 10000 bps".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Listing {
+    pub token_id: String,
+    pub seller: Addr,
+    pub price: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+// Breakdown of a sale price, whatever is left goes to the seller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SaleSplit {
+    pub royalty: Option<(Addr, Uint128)>,
+    pub artist_cut: Option<(Addr, Uint128)>,
+    pub platform_fee: Uint128,
+    pub seller_proceeds: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const ADMIN: Admin = Admin::new("admin");
+// Token id -> listing, the marketplace holds every listed token
+pub const LISTINGS: Map<&str, Listing> = Map::new("listings");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        nft_contract: deps.api.addr_validate(&msg.nft_contract)?,
+        denom: msg.denom,
+        platform_fee_bps: msg.platform_fee_bps,
+        fee_recipient: deps.api.addr_validate(&msg.fee_recipient)?,
+        artist_cut_bps: msg.artist_cut_bps,
+    };
+    config.validate()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+    ADMIN.set(deps.branch(), Some(admin))?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("nft_contract", config.nft_contract))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(receive) => execute_receive_nft(deps, info, receive),
+        ExecuteMsg::Delist { token_id } => execute_delist(deps, info, token_id),
+        ExecuteMsg::Buy { token_id } => execute_buy(deps, env, info, token_id),
+        ExecuteMsg::UpdatePrice { token_id, price } => execute_update_price(deps, info, token_id, price),
+        ExecuteMsg::UpdateConfig { platform_fee_bps, fee_recipient, artist_cut_bps } => {
+            execute_update_config(deps, info, platform_fee_bps, fee_recipient, artist_cut_bps)
+        }
+        ExecuteMsg::TransferAdmin { new_admin } => {
+            let new_admin = deps.api.addr_validate(&new_admin)?;
+            Ok(ADMIN.execute_update_admin(deps, info, Some(new_admin))?)
+        }
+    }
+}
+
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.nft_contract {
+        return Err(ContractError::UnknownCollection {});
+    }
+
+    let ReceiveMsg::List { price } = from_binary(&receive.msg)?;
+    if price.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+    // digm-nft already refuses to send soulbound tokens, listings never rely on that alone
+    assert_not_soulbound(deps.as_ref(), &config, &receive.token_id)?;
+
+    let listing = Listing {
+        token_id: receive.token_id.clone(),
+        seller: deps.api.addr_validate(&receive.sender)?,
+        price,
+    };
+    LISTINGS.save(deps.storage, &receive.token_id, &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "list")
+        .add_attribute("token_id", listing.token_id)
+        .add_attribute("seller", listing.seller)
+        .add_attribute("price", price))
+}
+
+pub fn execute_delist(deps: DepsMut, info: MessageInfo, token_id: String) -> Result<Response, ContractError> {
+    let listing = load_listing(deps.as_ref(), &token_id)?;
+    if listing.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    LISTINGS.remove(deps.storage, &token_id);
+
+    // digm-nft always lets a token go back to whoever sent it in, even one made soulbound while listed
+    let config = CONFIG.load(deps.storage)?;
+    Ok(Response::new()
+        .add_message(transfer_nft_msg(&config, &token_id, &listing.seller)?)
+        .add_attribute("method", "delist")
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_update_price(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    price: Uint128,
+) -> Result<Response, ContractError> {
+    let mut listing = load_listing(deps.as_ref(), &token_id)?;
+    if listing.seller != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if price.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+    listing.price = price;
+    LISTINGS.save(deps.storage, &token_id, &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_price")
+        .add_attribute("token_id", token_id)
+        .add_attribute("price", price))
+}
+
+pub fn execute_buy(deps: DepsMut, env: Env, info: MessageInfo, token_id: String) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let listing = load_listing(deps.as_ref(), &token_id)?;
+
+    let payment = must_pay(&info, &config.denom)?;
+    if payment != listing.price {
+        return Err(ContractError::IncorrectPayment { price: listing.price });
+    }
+
+    // The listing is only good while the marketplace still holds the token
+    let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+        &config.nft_contract,
+        &digm_nft::QueryMsg::OwnerOf {
+            token_id: token_id.clone(),
+            include_expired: None,
+        },
+    )?;
+    if owner.owner != env.contract.address {
+        return Err(ContractError::ListingNotHeld { token_id });
+    }
+    // Tokens made soulbound after listing cannot be sold, only delisted
+    let extension = assert_not_soulbound(deps.as_ref(), &config, &token_id)?;
+
+    let split = split_sale(deps.as_ref(), &config, &listing, &extension)?;
+    LISTINGS.remove(deps.storage, &token_id);
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut payouts = vec![(listing.seller.clone(), split.seller_proceeds)];
+    payouts.extend(split.royalty.clone());
+    payouts.extend(split.artist_cut.clone());
+    payouts.push((config.fee_recipient.clone(), split.platform_fee));
+    for (recipient, amount) in payouts {
+        if !amount.is_zero() {
+            messages.push(
+                BankMsg::Send {
+                    to_address: recipient.to_string(),
+                    amount: coins(amount.u128(), &config.denom),
+                }
+                .into(),
+            );
+        }
+    }
+    messages.push(transfer_nft_msg(&config, &token_id, &info.sender)?);
+
+    let royalty_amount = split.royalty.map(|(_, amount)| amount).unwrap_or_default();
+    let artist_cut = split.artist_cut.map(|(_, amount)| amount).unwrap_or_default();
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "buy")
+        .add_attribute("token_id", token_id)
+        .add_attribute("buyer", info.sender)
+        .add_attribute("seller", listing.seller)
+        .add_attribute("price", listing.price)
+        .add_attribute("royalty", royalty_amount)
+        .add_attribute("artist_cut", artist_cut)
+        .add_attribute("platform_fee", split.platform_fee))
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    platform_fee_bps: Option<u64>,
+    fee_recipient: Option<String>,
+    artist_cut_bps: Option<u64>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(platform_fee_bps) = platform_fee_bps {
+        config.platform_fee_bps = platform_fee_bps;
+    }
+    if let Some(fee_recipient) = fee_recipient {
+        config.fee_recipient = deps.api.addr_validate(&fee_recipient)?;
+    }
+    if let Some(artist_cut_bps) = artist_cut_bps {
+        config.artist_cut_bps = artist_cut_bps;
+    }
+    config.validate()?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_config")
+        .add_attribute("platform_fee_bps", config.platform_fee_bps.to_string())
+        .add_attribute("artist_cut_bps", config.artist_cut_bps.to_string()))
+}
+
+// Royalty per digm-nft's cw2981 extension, the artist cut to the album artist, the platform fee to the fee recipient
+fn split_sale(
+    deps: Deps,
+    config: &Config,
+    listing: &Listing,
+    extension: &ContractExtension,
+) -> Result<SaleSplit, ContractError> {
+    let royalty_info: RoyaltiesInfoResponse = deps.querier.query_wasm_smart(
+        &config.nft_contract,
+        &digm_nft::QueryMsg::Extension {
+            msg: Cw2981QueryMsg::RoyaltyInfo {
+                token_id: listing.token_id.clone(),
+                sale_price: listing.price,
+            },
+        },
+    )?;
+    let royalty = if royalty_info.royalty_amount.is_zero() {
+        None
+    } else {
+        Some((deps.api.addr_validate(&royalty_info.address)?, royalty_info.royalty_amount))
+    };
+
+    // Tokens minted without an album have no artist to pay
+    let artist_cut = match &extension.album {
+        Some(album) if config.artist_cut_bps > 0 => Some((
+            deps.api.addr_validate(&album.artist)?,
+            listing.price.multiply_ratio(config.artist_cut_bps, BPS_DENOMINATOR),
+        )),
+        _ => None,
+    };
+    let platform_fee = listing.price.multiply_ratio(config.platform_fee_bps, BPS_DENOMINATOR);
+
+    let deductions = [&royalty, &artist_cut]
+        .into_iter()
+        .flatten()
+        .try_fold(platform_fee, |total, (_, amount)| total.checked_add(*amount))
+        .map_err(StdError::from)?;
+    let seller_proceeds = listing
+        .price
+        .checked_sub(deductions)
+        .map_err(|_| ContractError::FeesExceedPrice { price: listing.price })?;
+
+    Ok(SaleSplit {
+        royalty,
+        artist_cut,
+        platform_fee,
+        seller_proceeds,
+    })
+}
+
+fn assert_not_soulbound(deps: Deps, config: &Config, token_id: &str) -> Result<ContractExtension, ContractError> {
+    let info: NftInfoResponse<ContractExtension> = deps.querier.query_wasm_smart(
+        &config.nft_contract,
+        &digm_nft::QueryMsg::NftInfo {
+            token_id: token_id.to_string(),
+        },
+    )?;
+    if info.extension.soulbound {
+        return Err(ContractError::Soulbound {
+            token_id: token_id.to_string(),
+        });
+    }
+    Ok(info.extension)
+}
+
+fn load_listing(deps: Deps, token_id: &str) -> Result<Listing, ContractError> {
+    LISTINGS
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::ListingNotFound {
+            token_id: token_id.to_string(),
+        })
+}
+
+fn transfer_nft_msg(config: &Config, token_id: &str, recipient: &Addr) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.nft_contract.to_string(),
+        msg: to_binary(&digm_nft::ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetConfig {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::GetAdmin {} => to_binary(&ADMIN.query_admin(deps)?),
+        QueryMsg::GetListing { token_id } => to_binary(&LISTINGS.load(deps.storage, &token_id)?),
+        QueryMsg::GetListings { start_after, limit } => {
+            to_binary(&query_listings(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_listings(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let listings = LISTINGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, listing)| listing))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListingsResponse { listings })
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid config: {reason}")]
+    InvalidConfig { reason: String },
+
+    #[error("Only tokens of the configured collection can be listed")]
+    UnknownCollection {},
+
+    #[error("Listing price must be positive")]
+    InvalidPrice {},
+
+    #[error("Token {token_id} is not listed")]
+    ListingNotFound { token_id: String },
+
+    #[error("Token {token_id} is no longer held by the marketplace")]
+    ListingNotHeld { token_id: String },
+
+    #[error("Token {token_id} is soulbound")]
+    Soulbound { token_id: String },
+
+    #[error("Payment must be exactly {price}")]
+    IncorrectPayment { price: Uint128 },
+
+    #[error("Royalty and fees exceed the price {price}")]
+    FeesExceedPrice { price: Uint128 },
+}
+
+#[cfg(test)]
+mod tests;
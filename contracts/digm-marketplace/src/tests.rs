@@ -0,0 +1,211 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{ContractResult, OwnedDeps, SystemResult, WasmQuery};
+use cw721::{NftInfoResponse, OwnerOfResponse};
+use digm_nft::AlbumMetadata;
+
+use super::*;
+
+const NFT: &str = "collection";
+const DENOM: &str = "ujuno";
+const TOKEN: &str = "curve-1";
+
+type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+fn setup() -> TestDeps {
+    let mut deps = mock_dependencies();
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        InstantiateMsg {
+            admin: None,
+            nft_contract: NFT.to_string(),
+            denom: DENOM.to_string(),
+            platform_fee_bps: 250,
+            fee_recipient: "treasury".to_string(),
+            artist_cut_bps: 500,
+        },
+    )
+    .unwrap();
+    mock_collection(&mut deps, MOCK_CONTRACT_ADDR, false);
+    deps
+}
+
+// Answers for the digm-nft collection: the token's owner, its extension and a 10% royalty
+fn mock_collection(deps: &mut TestDeps, owner: &str, soulbound: bool) {
+    let owner = owner.to_string();
+    deps.querier.update_wasm(move |query| {
+        let msg = match query {
+            WasmQuery::Smart { msg, .. } => from_binary::<digm_nft::QueryMsg>(msg).unwrap(),
+            _ => panic!("unexpected query {:?}", query),
+        };
+        let res = match msg {
+            digm_nft::QueryMsg::OwnerOf { .. } => to_binary(&OwnerOfResponse {
+                owner: owner.clone(),
+                approvals: vec![],
+            }),
+            digm_nft::QueryMsg::NftInfo { .. } => to_binary(&NftInfoResponse {
+                token_uri: None,
+                extension: ContractExtension {
+                    album: Some(album()),
+                    soulbound,
+                    ..ContractExtension::default()
+                },
+            }),
+            digm_nft::QueryMsg::Extension {
+                msg: Cw2981QueryMsg::RoyaltyInfo { sale_price, .. },
+            } => to_binary(&RoyaltiesInfoResponse {
+                address: "royalties".to_string(),
+                royalty_amount: sale_price.multiply_ratio(1u128, 10u128),
+            }),
+            msg => panic!("unexpected query {:?}", msg),
+        };
+        SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+    });
+}
+
+fn album() -> AlbumMetadata {
+    AlbumMetadata {
+        album_id: "album-1".to_string(),
+        title: "Album".to_string(),
+        tracks: vec![],
+        content_root: String::new(),
+        cover_hash: String::new(),
+        artist: "artist".to_string(),
+        license_terms: String::new(),
+    }
+}
+
+fn list(deps: &mut TestDeps, sender: &str, price: u128) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: "seller".to_string(),
+            token_id: TOKEN.to_string(),
+            msg: to_binary(&ReceiveMsg::List { price: Uint128::new(price) }).unwrap(),
+        }),
+    )
+}
+
+fn buy(deps: &mut TestDeps, amount: u128) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("buyer", &coins(amount, DENOM)),
+        ExecuteMsg::Buy { token_id: TOKEN.to_string() },
+    )
+}
+
+fn delist(deps: &mut TestDeps, sender: &str) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::Delist { token_id: TOKEN.to_string() },
+    )
+}
+
+fn bank_send(recipient: &str, amount: u128) -> CosmosMsg {
+    BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: coins(amount, DENOM),
+    }
+    .into()
+}
+
+fn nft_transfer(deps: &TestDeps, recipient: &str) -> CosmosMsg {
+    let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    transfer_nft_msg(&config, TOKEN, &Addr::unchecked(recipient)).unwrap()
+}
+
+fn listing(deps: &TestDeps) -> Option<Listing> {
+    LISTINGS.may_load(deps.as_ref().storage, TOKEN).unwrap()
+}
+
+#[test]
+fn list_only_from_the_collection_at_a_price() {
+    let mut deps = setup();
+
+    assert_eq!(list(&mut deps, "other", 1_000).unwrap_err(), ContractError::UnknownCollection {});
+    assert_eq!(list(&mut deps, NFT, 0).unwrap_err(), ContractError::InvalidPrice {});
+
+    list(&mut deps, NFT, 1_000).unwrap();
+    assert_eq!(
+        listing(&deps),
+        Some(Listing {
+            token_id: TOKEN.to_string(),
+            seller: Addr::unchecked("seller"),
+            price: Uint128::new(1_000),
+        })
+    );
+}
+
+#[test]
+fn buy_splits_the_price() {
+    let mut deps = setup();
+    list(&mut deps, NFT, 1_000).unwrap();
+
+    assert_eq!(
+        buy(&mut deps, 999).unwrap_err(),
+        ContractError::IncorrectPayment { price: Uint128::new(1_000) }
+    );
+
+    let res = buy(&mut deps, 1_000).unwrap();
+    let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|sub| sub.msg).collect();
+    assert_eq!(
+        messages,
+        vec![
+            // 1000 less a 100 royalty, a 50 artist cut and a 25 platform fee
+            bank_send("seller", 825),
+            bank_send("royalties", 100),
+            bank_send("artist", 50),
+            bank_send("treasury", 25),
+            nft_transfer(&deps, "buyer"),
+        ]
+    );
+    assert_eq!(listing(&deps), None);
+}
+
+#[test]
+fn buy_needs_the_marketplace_to_hold_the_token() {
+    let mut deps = setup();
+    list(&mut deps, NFT, 1_000).unwrap();
+    mock_collection(&mut deps, "seller", false);
+
+    assert_eq!(
+        buy(&mut deps, 1_000).unwrap_err(),
+        ContractError::ListingNotHeld { token_id: TOKEN.to_string() }
+    );
+}
+
+#[test]
+fn delist_returns_the_token_to_the_seller() {
+    let mut deps = setup();
+    list(&mut deps, NFT, 1_000).unwrap();
+
+    assert_eq!(delist(&mut deps, "buyer").unwrap_err(), ContractError::Unauthorized {});
+
+    let res = delist(&mut deps, "seller").unwrap();
+    assert_eq!(res.messages[0].msg, nft_transfer(&deps, "seller"));
+    assert_eq!(listing(&deps), None);
+}
+
+#[test]
+fn tokens_made_soulbound_after_listing_can_be_delisted() {
+    let mut deps = setup();
+    list(&mut deps, NFT, 1_000).unwrap();
+    mock_collection(&mut deps, MOCK_CONTRACT_ADDR, true);
+
+    assert_eq!(
+        buy(&mut deps, 1_000).unwrap_err(),
+        ContractError::Soulbound { token_id: TOKEN.to_string() }
+    );
+
+    let res = delist(&mut deps, "seller").unwrap();
+    assert_eq!(res.messages[0].msg, nft_transfer(&deps, "seller"));
+    assert_eq!(listing(&deps), None);
+}
@@ -1,48 +1,208 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo,
-    Response, StdResult, Uint128, Addr, CosmosMsg, WasmMsg,
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, Addr, Api, Decimal, BankMsg, coin, coins, Coin, Order, Storage,
+    Event, Timestamp,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw_controllers::{Admin, AdminError, AdminResponse};
 use cw721_base::{ContractError as Cw721Error, Cw721Contract, InstantiateMsg as Cw721InstantiateMsg};
 use cw721_base::state::TokenInfo;
-use cw_storage_plus::Map;
+use cw_utils::Expiration;
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, SnapshotMap, Strategy,
+};
+use hex::FromHexError;
 use schemars::JsonSchema;
+use semver::Version;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 const CONTRACT_NAME: &str = "crates.io:digm-nft";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-// Bonding curve parameters
+// Bonding curve parameters, supply caps can be changed later through UpdateConfig
 const CURVE_SUPPLY: u32 = 5000;
 const CONTRIBUTION_SUPPLY: u32 = 5000;
-const TOTAL_SUPPLY: u32 = CURVE_SUPPLY + CONTRIBUTION_SUPPLY;
+const DEFAULT_SELL_SPREAD_BPS: u64 = 1000;
+const BPS_DENOMINATOR: u64 = 10_000;
+const MAX_BATCH_MINT: u32 = 50;
+const MAX_PAYEES: usize = 10;
+const MAX_AUCTION_SCHEDULE: u64 = 100;
+const DEFAULT_VOTING_PERIOD: u64 = 7 * 24 * 60 * 60;
+const DEFAULT_QUORUM_BPS: u64 = 2000;
+const DEFAULT_THRESHOLD_BPS: u64 = 5000;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+// Version of the attribute schema carried by every `digm_*` event, bumped on incompatible changes.
+// Version 1:
+//   digm_mint          token_id, owner, kind ("curve" | "contribution" | "bridge" | "minter"),
+//                      price and denom for curve mints, tier and contribution_points for contribution mints
+//   digm_transfer      token_id, from, to, kind ("transfer" | "send" | "recover")
+//   digm_burn          token_id, owner, kind ("burn" | "sell"), payout and denom for sell-backs
+//   digm_tier_upgrade  token_id, owner, from_tier, to_tier, contribution_points
+//   digm_config_update sender, fields (comma-separated names of the settings changed),
+//                      sender is the contract itself when a governance proposal made the change
+pub const EVENT_SCHEMA_VERSION: &str = "1";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub name: String,
     pub symbol: String,
     pub minter: String,
+    // Contract admin, defaults to the minter
+    pub admin: Option<String>,
+    // The curve is left out by ics721, which instantiates voucher collections with cw721-base's message
+    #[serde(default)]
     pub curve_start_price: Uint128,
+    #[serde(default)]
     pub curve_rate: Uint128, // Rate per NFT (e.g., 1000000 = 0.001 tokens)
+    // Price shape applied to start price and rate, defaults to linear
+    pub curve_type: Option<CurveType>,
+    // Denom the curve is priced in and the reserve is held in, none makes a voucher collection
+    #[serde(default)]
+    pub curve_denom: String,
+    // Discount applied to sell-backs, in basis points of the curve price
+    pub sell_spread_bps: Option<u64>,
+    // Hex-encoded sha256 Merkle root of (address, contribution_points) leaves
+    pub contribution_root: Option<String>,
+    // Collection-wide royalty, tokens can override it individually
+    pub royalty: Option<Royalty>,
+    // Split of curve revenue, defaults to the admin taking all of it
+    pub payees: Option<Vec<Payee>>,
+    // Token URI template, `{token_id}` and `{tier}` are substituted at mint
+    pub base_uri: Option<String>,
+    // Sale schedule for curve mints, no phases keeps minting open to everyone
+    pub phases: Option<Vec<MintPhase>>,
+    // How curve mints are priced, defaults to the bonding curve
+    pub sale_mode: Option<SaleMode>,
+    // Album every token in the collection is minted with
+    pub album: Option<AlbumMetadata>,
+    // Mint contribution tokens as non-transferable
+    pub soulbound_contributions: Option<bool>,
+    // Supply caps, default to 5000 curve and 5000 contribution tokens
+    pub supply: Option<SupplyConfig>,
+    // Cut of curve revenue taken before payees, fixed for the life of the collection
+    pub platform_fee: Option<PlatformFee>,
+    // Holder voting rules, default to a 7 day period, 20% quorum and a simple majority
+    pub governance: Option<GovernanceConfig>,
+    // Extra payment denoms and a quote unit such as USD, priced through an oracle
+    pub pricing: Option<PricingConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    // Only read when migrating from 0.1.x, which had no admin or curve denom
+    pub admin: Option<String>,
+    pub curve_denom: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    // Bonding curve mint
-    MintCurve {},
+    // Bonding curve mint, the proof is only needed during allowlist phases
+    MintCurve { allowlist_proof: Option<Vec<String>> },
+    // Mint `count` curve tokens, reverting if the summed price exceeds `max_total_price` (in the pricing base unit)
+    MintCurveBatch {
+        count: u32,
+        max_total_price: Uint128,
+        allowlist_proof: Option<Vec<String>>,
+    },
     // Contribution-based mint
     MintContribution {
         proof: Vec<String>,
         contribution_points: Uint128,
     },
+    // Contribution mint attested by a registered signer instead of a Merkle proof
+    MintContributionSigned {
+        points: Uint128,
+        nonce: u64,
+        expires: u64,
+        signature: Binary,
+    },
+    // Raise the tier of a contribution token the sender owns and claimed, against the current root
+    UpgradeTier {
+        token_id: String,
+        proof: Vec<String>,
+        new_points: Uint128,
+    },
+    // Raise the tier of a contribution token with an attestation of the sender's current points,
+    // signed the same way as for MintContributionSigned
+    UpgradeTierSigned {
+        token_id: String,
+        points: Uint128,
+        nonce: u64,
+        expires: u64,
+        signature: Binary,
+    },
+    // Replace the contribution Merkle root (admin only)
+    SetContributionRoot { root: String },
+    // Rotate attestation signers (admin only)
+    UpdateSigners { add: Vec<Signer>, remove: Vec<Binary> },
+    // Burn a curve token for the curve's current sell price, paid out of the reserve
+    SellCurve { token_id: String },
+    // Fix the clearing price of the current auction (admin anytime, anyone once it hits the floor)
+    SettleAuction {},
+    // Refund what the sender paid above the clearing price of a settled auction
+    ClaimRebate { auction_id: u64 },
+    // Override or clear the royalty of a single token (admin only)
+    SetTokenRoyalty { token_id: String, royalty: Option<Royalty> },
+    // Lock or unlock transfers of a single token (admin only)
+    SetSoulbound { token_id: String, soulbound: bool },
+    // Change the album a single token licenses, None revokes its license (admin only)
+    SetLicenseScope { token_id: String, album_id: Option<String> },
+    // Lend a token's listening rights until `expires` (seconds), None clears the user
+    SetUser { token_id: String, user: Option<String>, expires: u64 },
+    // Move a soulbound token out of a lost wallet (admin only)
+    RecoverToken { token_id: String, recipient: String },
+    // Admin controls
+    UpdateConfig(ConfigUpdate),
+    // Halts minting, burning, selling back and transferring until unpaused
+    Pause {},
+    Unpause {},
+    TransferAdmin { new_admin: String },
+    // Replace a token's album metadata (artist or admin, until frozen)
+    UpdateMetadata { token_id: String, album: AlbumMetadata },
+    // Permanently lock token metadata, the base URI and the collection album
+    FreezeMetadata {},
+    // Mirror the origin collection from the ics721 class data (minter of a voucher collection only)
+    SetClassData { class_data: Binary },
+    // Pay the sender's share of curve revenue
+    Withdraw {},
+    // Pay every payee their outstanding share
+    DistributeProceeds {},
+    // Open a vote among silver and gold holders, weighted by tier as held at this block
+    Propose {
+        title: String,
+        description: String,
+        action: ProposalAction,
+    },
+    // A proposal that can no longer fail is executed by the vote deciding it
+    Vote { proposal_id: u64, vote: VoteOption },
+    // Tally a proposal once voting ends, executing it if it passed
+    CloseProposal { proposal_id: u64 },
     // Standard cw721 messages
+    UpdateOwnership(cw721_base::Action),
+    // Mint outside the curve and contribution supply (minter only), which is how ics721 mints vouchers.
+    // `extension` is the origin token data as returned by TokenData, ics721 may send it empty
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: Option<ContractExtension>,
+    },
     TransferNft { recipient: String, token_id: String },
     SendNft { contract: String, token_id: String, msg: Binary },
-    Approve { spender: String, token_id: String, expires: Option<u64> },
+    Approve { spender: String, token_id: String, expires: Option<Expiration> },
     Revoke { spender: String, token_id: String },
-    ApproveAll { operator: String, expires: Option<u64> },
+    ApproveAll { operator: String, expires: Option<Expiration> },
     RevokeAll { operator: String },
+    // Burning frees the token's slot in the curve or contribution supply, the minter burns what it minted
+    Burn { token_id: String },
+    // cw721-base's extension hook, accepted for compatibility and a no-op
+    Extension { msg: Empty },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -50,17 +210,82 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     // Bonding curve queries
     GetCurvePrice {},
+    GetCurveConfig {},
+    GetReserve {},
+    // Reserve share a curve token was minted with
+    GetBacking { token_id: String },
+    SimulateMint { count: u32 },
+    // What the next `count` mints cost when paid in `denom`
+    QuotePayment { denom: String, count: u32 },
+    GetMintPhases {},
+    GetPhaseMints { phase: String, address: String },
+    GetSaleMode {},
+    // Current Dutch auction with its price schedule
+    GetAuction {},
+    GetRebate { auction_id: u64, address: String },
     GetCurveSupply {},
     GetContributionSupply {},
+    GetContributionRoot {},
+    GetSigners {},
+    GetAdmin {},
+    GetConfig {},
+    GetPayees {},
+    GetMetadataConfig {},
+    GetClaimable { address: String },
+    // Tier index queries
+    TokensByTier { tier: String, start_after: Option<String>, limit: Option<u32> },
+    TierCounts {},
+    OwnerTierSummary { owner: String },
+    // Album license checks, for key release by Elderfiers
+    HasEntitlement { owner: String, album_id: String },
+    EntitlementsOf { owner: String },
+    // Active renter of a token
+    UserOf { token_id: String },
+    // What an ics721 bridge carries as class and token data
+    ClassData {},
+    TokenData { token_id: String },
+    // Holder governance
+    GetGovernanceConfig {},
+    GetProposal { proposal_id: u64 },
+    ListProposals { start_after: Option<u64>, limit: Option<u32> },
+    GetVote { proposal_id: u64, voter: String },
+    // Weight as of the start of `height`, defaults to the current weight
+    VotingPower { address: String, height: Option<u64> },
+    // cw2981 royalty queries
+    Extension { msg: Cw2981QueryMsg },
     // Standard cw721 queries
     OwnerOf { token_id: String, include_expired: Option<bool> },
     Approval { token_id: String, spender: String, include_expired: Option<bool> },
-    ApprovalsFor { token_id: String, include_expired: Option<bool> },
+    Approvals { token_id: String, include_expired: Option<bool> },
+    Operator { owner: String, operator: String, include_expired: Option<bool> },
     AllOperators { owner: String, include_expired: Option<bool>, start_after: Option<String>, limit: Option<u32> },
-    AllTokens { start_after: Option<String>, limit: Option<u32> },
-    Tokens { owner: String, start_after: Option<String>, limit: Option<u32> },
-    ContractInfo {},
     NumTokens {},
+    ContractInfo {},
+    NftInfo { token_id: String },
+    AllNftInfo { token_id: String, include_expired: Option<bool> },
+    Tokens { owner: String, start_after: Option<String>, limit: Option<u32> },
+    AllTokens { start_after: Option<String>, limit: Option<u32> },
+    Minter {},
+    Ownership {},
+}
+
+// Mirrors the cw2981-royalties query extension so marketplaces can query it unchanged
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw2981QueryMsg {
+    RoyaltyInfo { token_id: String, sale_price: Uint128 },
+    CheckRoyalties {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -78,215 +303,3839 @@ pub struct CurveInfoResponse {
     pub max_supply: u32,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReserveResponse {
+    pub denom: String,
+    pub reserve: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BackingResponse {
+    pub token_id: String,
+    // In the curve denom, what the token paid into the reserve, none for tokens that cannot be sold back
+    pub backing: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateMintResponse {
+    pub total_price: Uint128,
+    pub prices: Vec<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PaymentQuoteResponse {
+    pub denom: String,
+    // Units of `denom` per unit of the pricing base, None when no conversion applies
+    pub rate: Option<Decimal>,
+    pub total_price: Uint128,
+    pub prices: Vec<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveType {
+    // price = start_price + rate * supply
+    Linear {},
+    // price = start_price * (1 + growth) ^ supply, rate is unused
+    Exponential { growth: Decimal },
+    // price = start_price + rate * supply ^ exponent
+    Polynomial { exponent: u32 },
+    // price = start_price until the first step, then the price of the last step reached
+    PiecewiseStep { steps: Vec<CurveStep> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurveStep {
+    pub from_supply: u32,
+    pub price: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurveConfig {
+    pub curve_type: CurveType,
+    pub start_price: Uint128,
+    pub rate: Uint128,
+    pub denom: String,
+    pub sell_spread_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupplyConfig {
+    pub curve_supply: u32,
+    pub contribution_supply: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ConfigUpdate {
+    pub curve: Option<CurveConfig>,
+    pub contribution_root: Option<String>,
+    pub supply: Option<SupplyConfig>,
+    pub default_royalty: Option<Royalty>,
+    pub payees: Option<Vec<Payee>>,
+    pub base_uri: Option<String>,
+    pub album: Option<AlbumMetadata>,
+    pub phases: Option<Vec<MintPhase>>,
+    pub sale_mode: Option<SaleMode>,
+    pub soulbound_contributions: Option<bool>,
+    // Only applies to proposals opened afterwards
+    pub governance: Option<GovernanceConfig>,
+    pub pricing: Option<PricingConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SaleMode {
+    // Prices follow the curve and tokens can be sold back against the reserve
+    BondingCurve {},
+    // Prices decay over time down to a floor, sell-backs are disabled
+    DutchAuction(DutchAuctionConfig),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DutchAuctionConfig {
+    pub start_price: Uint128,
+    pub floor_price: Uint128,
+    pub start_time: Timestamp,
+    // The price drops by `step_decrease` every `step_seconds` until it reaches the floor
+    pub step_seconds: u64,
+    pub step_decrease: Uint128,
+    // Hold proceeds until settlement so earlier buyers can claim back the difference to the clearing price
+    pub rebate: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct AuctionState {
+    pub sold: u32,
+    // In the pricing base unit
+    pub proceeds: Uint128,
+    pub last_price: Option<Uint128>,
+    // Set at settlement, no more auction mints afterwards
+    pub clearing_price: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionPurchase {
+    pub count: u32,
+    pub paid: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionStep {
+    pub time: Timestamp,
+    pub price: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionResponse {
+    pub auction_id: u64,
+    pub config: DutchAuctionConfig,
+    pub current_price: Uint128,
+    pub floor_reached_at: Timestamp,
+    pub state: AuctionState,
+    // Price changes from the start up to the floor, truncated to the first 100
+    pub schedule: Vec<AuctionStep>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RebateResponse {
+    pub auction_id: u64,
+    pub address: String,
+    pub count: u32,
+    pub paid: Uint128,
+    // Unknown until the auction settles
+    pub rebate: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseKind {
+    // Leaves are sha256 of the address, built like the contribution tree
+    Allowlist { merkle_root: String },
+    Public {},
+    Closed {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintPhase {
+    pub name: String,
+    pub kind: PhaseKind,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    // Flat price replacing the curve price while the phase runs
+    pub price: Option<Uint128>,
+    pub max_per_address: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintPhasesResponse {
+    pub phases: Vec<MintPhase>,
+    pub active: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PhaseMintsResponse {
+    pub phase: String,
+    pub address: String,
+    pub minted: u32,
+    pub max_per_address: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MetadataConfig {
+    pub base_uri: Option<String>,
+    pub frozen: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MetadataConfigResponse {
+    pub base_uri: Option<String>,
+    pub frozen: bool,
+    pub album: Option<AlbumMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payee {
+    pub address: String,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlatformFee {
+    pub recipient: String,
+    pub bps: u64,
+}
+
+impl PlatformFee {
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        api.addr_validate(&self.recipient)?;
+        if self.bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidPlatformFee { bps: self.bps });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PricingConfig {
+    // Contract answering `OracleQueryMsg::ExchangeRate`
+    pub oracle: String,
+    // Unit curve, phase and auction prices are quoted in, e.g. "usd". None quotes them in the curve denom
+    pub base_unit: Option<String>,
+    // Denoms accepted besides the curve denom
+    pub denoms: Vec<String>,
+    // Oldest oracle rate accepted, in seconds
+    pub max_staleness: u64,
+    // Largest move from the last rate accepted for a denom, in basis points
+    pub max_deviation_bps: u64,
+}
+
+impl PricingConfig {
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        api.addr_validate(&self.oracle)?;
+        if self.base_unit.as_deref() == Some("") {
+            return Err(ContractError::InvalidPricing {
+                reason: "base unit cannot be empty".to_string(),
+            });
+        }
+        if self.max_staleness == 0 || self.max_deviation_bps == 0 {
+            return Err(ContractError::InvalidPricing {
+                reason: "max staleness and max deviation must be positive".to_string(),
+            });
+        }
+        for (i, denom) in self.denoms.iter().enumerate() {
+            if denom.is_empty() || self.denoms[..i].contains(denom) {
+                return Err(ContractError::InvalidPricing {
+                    reason: format!("denoms must be unique and non-empty: {:?}", denom),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+// Exchange-rate query the pricing oracle has to answer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    // Units of `quote` worth one unit of `base`
+    ExchangeRate { base: String, quote: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    pub rate: Decimal,
+    pub updated_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeesResponse {
+    pub payees: Vec<Payee>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimableResponse {
+    pub address: String,
+    // One coin per denom curve revenue was paid in
+    pub claimable: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub admin: Option<String>,
+    pub paused: bool,
+    pub supply: SupplyConfig,
+    pub default_royalty: Option<Royalty>,
+    pub soulbound_contributions: bool,
+    pub platform_fee: Option<PlatformFee>,
+    pub governance: GovernanceConfig,
+    pub pricing: Option<PricingConfig>,
+    pub voucher_collection: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    Secp256k1,
+    Ed25519,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Signer {
+    pub pubkey: Binary,
+    pub scheme: SignatureScheme,
+}
+
+impl Signer {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        // Compressed or uncompressed secp256k1 keys, raw ed25519 keys
+        let valid = match self.scheme {
+            SignatureScheme::Secp256k1 => matches!(self.pubkey.len(), 33 | 65),
+            SignatureScheme::Ed25519 => self.pubkey.len() == 32,
+        };
+        if !valid {
+            return Err(ContractError::InvalidSigner {});
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignersResponse {
+    pub signers: Vec<Signer>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensByTierResponse {
+    pub tier: String,
+    pub tokens: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierCount {
+    pub tier: String,
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierCountsResponse {
+    pub tiers: Vec<TierCount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerTierSummaryResponse {
+    pub owner: String,
+    pub tiers: Vec<TierCount>,
+    pub highest_tier: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Entitlement {
+    pub album_id: String,
+    pub token_id: String,
+    // Set when the license is rented rather than owned
+    pub expires: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserOfResponse {
+    pub user: Option<String>,
+    pub expires: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HasEntitlementResponse {
+    pub entitled: bool,
+    // A token granting the license, when there is one
+    pub token_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EntitlementsResponse {
+    pub owner: String,
+    pub entitlements: Vec<Entitlement>,
+}
+
+// Collection-level metadata carried as ics721 class data, so voucher collections can mirror their origin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClassData {
+    pub name: String,
+    pub symbol: String,
+    pub album: Option<AlbumMetadata>,
+    pub default_royalty: Option<Royalty>,
+    pub base_uri: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenDataResponse {
+    pub token_uri: Option<String>,
+    // JSON-encoded `ContractExtension`, minted unchanged as the extension of the voucher
+    pub token_data: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContributionRootResponse {
+    pub root: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GovernanceConfig {
+    // Seconds a proposal stays open
+    pub voting_period: u64,
+    // Share of the snapshot weight that has to vote, in basis points
+    pub quorum_bps: u64,
+    // Share of yes among yes and no votes a proposal has to exceed, in basis points
+    pub threshold_bps: u64,
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        GovernanceConfig {
+            voting_period: DEFAULT_VOTING_PERIOD,
+            quorum_bps: DEFAULT_QUORUM_BPS,
+            threshold_bps: DEFAULT_THRESHOLD_BPS,
+        }
+    }
+}
+
+impl GovernanceConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.voting_period == 0 {
+            return Err(ContractError::InvalidGovernanceConfig {
+                reason: "voting period must be positive".to_string(),
+            });
+        }
+        if self.quorum_bps == 0 || self.quorum_bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidGovernanceConfig {
+                reason: "quorum must be between 1 and 10000 bps".to_string(),
+            });
+        }
+        if self.threshold_bps >= BPS_DENOMINATOR {
+            return Err(ContractError::InvalidGovernanceConfig {
+                reason: "threshold must be below 10000 bps".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+// Config changes holders can vote on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalAction {
+    SetCurveRate { rate: Uint128 },
+    // Collection default royalty, its payment address is kept
+    SetRoyaltyBps { bps: u64 },
+    SetPayees { payees: Vec<Payee> },
+    SetContributionRoot { root: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes {},
+    No {},
+    Abstain {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    Open {},
+    Rejected {},
+    Executed {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Addr,
+    pub title: String,
+    pub description: String,
+    pub action: ProposalAction,
+    // Votes are weighted as holdings stood at the start of this block
+    pub snapshot_height: u64,
+    pub expires: Timestamp,
+    // Rules in force when the proposal was opened
+    pub quorum_bps: u64,
+    pub threshold_bps: u64,
+    pub total_weight: u64,
+    pub yes: u64,
+    pub no: u64,
+    pub abstain: u64,
+    pub status: ProposalStatus,
+}
+
+impl Proposal {
+    fn quorum_reached(&self, votes: u64) -> bool {
+        votes as u128 * BPS_DENOMINATOR as u128 >= self.quorum_bps as u128 * self.total_weight as u128
+    }
+
+    // Final outcome once voting has ended
+    pub fn passed(&self) -> bool {
+        self.quorum_reached(self.yes + self.no + self.abstain)
+            && self.yes as u128 * BPS_DENOMINATOR as u128
+                > self.threshold_bps as u128 * (self.yes + self.no) as u128
+    }
+
+    // Yes votes alone reach quorum and stay above the threshold even if all remaining weight votes no
+    pub fn decided(&self) -> bool {
+        self.quorum_reached(self.yes)
+            && self.yes as u128 * BPS_DENOMINATOR as u128
+                > self.threshold_bps as u128 * self.total_weight as u128
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalsResponse {
+    pub proposals: Vec<Proposal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteResponse {
+    pub vote: Option<VoteOption>,
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    pub address: String,
+    pub weight: u64,
+    pub total_weight: u64,
+}
+
 // Storage for bonding curve state
-pub const CURVE_MINTED: Map<&str, u32> = Map::new("curve_minted");
-pub const CONTRIBUTION_MINTED: Map<&str, u32> = Map::new("contribution_minted");
-pub const CONTRIBUTION_PROOFS: Map<&str, bool> = Map::new("contribution_proofs");
+pub const CURVE_MINTED: Item<u32> = Item::new("curve_minted_count");
+// Curve token ids keep increasing when tokens are sold back and the supply shrinks
+pub const CURVE_TOKEN_SEQ: Item<u32> = Item::new("curve_token_seq");
+pub const CURVE_RESERVE: Item<Uint128> = Item::new("curve_reserve");
+// Reserve share each curve token paid in at mint, leaves the reserve with the token when it is burned
+pub const CURVE_BACKING: Map<&str, Uint128> = Map::new("curve_backing");
+pub const MINT_PHASES: Item<Vec<MintPhase>> = Item::new("mint_phases");
+// (phase name, minter) -> curve tokens minted during that phase
+pub const PHASE_MINTS: Map<(&str, &Addr), u32> = Map::new("phase_mints");
+pub const SALE_MODE: Item<SaleMode> = Item::new("sale_mode");
+// Every auction configured gets a new id, the latest one is the current auction
+pub const AUCTION_ID: Item<u64> = Item::new("auction_id");
+pub const AUCTIONS: Map<u64, AuctionState> = Map::new("auctions");
+// (auction id, buyer) -> what the buyer paid in a rebate auction, removed once the rebate is claimed
+pub const AUCTION_PURCHASES: Map<(u64, &Addr), AuctionPurchase> = Map::new("auction_purchases");
+// Rebate auction proceeds held until settlement, then only the unclaimed rebates
+pub const AUCTION_ESCROW: Item<Uint128> = Item::new("auction_escrow");
+pub const CONTRIBUTION_MINTED: Item<u32> = Item::new("contribution_minted_count");
+// Contribution token ids keep increasing when tokens are burned
+pub const CONTRIBUTION_TOKEN_SEQ: Item<u32> = Item::new("contribution_token_seq");
+// Contributor address -> hex-encoded Merkle leaf or attestation digest they claimed
+pub const CONTRIBUTION_PROOFS: Map<&Addr, String> = Map::new("contribution_proofs");
+// Address whose allocation each contribution token was minted from, the only one that can upgrade it.
+// Tokens migrated from 0.1.x have none, their claims were never recorded
+pub const CONTRIBUTION_CLAIMANTS: Map<&str, Addr> = Map::new("contribution_claimants");
+pub const CURVE_CONFIG: Item<CurveConfig> = Item::new("curve_config");
+pub const CONTRIBUTION_ROOT: Item<String> = Item::new("contribution_root");
+// Attestation signers keyed by public key, and the nonces each has spent
+pub const SIGNERS: Map<&[u8], Signer> = Map::new("signers");
+pub const USED_NONCES: Map<(&[u8], u64), bool> = Map::new("used_nonces");
+pub const SUPPLY_CONFIG: Item<SupplyConfig> = Item::new("supply_config");
+pub const DEFAULT_ROYALTY: Item<Royalty> = Item::new("default_royalty");
+// Tokens carrying their own royalty, so royalties can apply without a collection default
+pub const ROYALTY_OVERRIDES: Item<u32> = Item::new("royalty_overrides");
+// Applies to contribution tokens minted from now on, existing tokens keep their own flag
+pub const SOULBOUND_CONTRIBUTIONS: Item<bool> = Item::new("soulbound_contributions");
 
-#[entry_point]
-pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+// Token id -> tier, indexed by tier. Only tokens that carry a tier are stored
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenTier {
+    pub tier: String,
+}
+
+pub struct TokenTierIndexes<'a> {
+    pub tier: MultiIndex<'a, String, TokenTier, String>,
+}
+
+impl<'a> IndexList<TokenTier> for TokenTierIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenTier>> + '_> {
+        let v: Vec<&dyn Index<TokenTier>> = vec![&self.tier];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn token_tiers<'a>() -> IndexedMap<'a, &'a str, TokenTier, TokenTierIndexes<'a>> {
+    let indexes = TokenTierIndexes {
+        tier: MultiIndex::new(|_pk, entry| entry.tier.clone(), "token_tiers", "token_tiers__tier"),
+    };
+    IndexedMap::new("token_tiers", indexes)
+}
+
+pub const TIER_COUNTS: Map<&str, u32> = Map::new("tier_counts");
+
+// Token id -> renter, indexed by renter. Cleared whenever the token changes hands
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenUser {
+    pub user: Addr,
+    pub expires: u64,
+}
+
+impl TokenUser {
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        now.seconds() < self.expires
+    }
+}
+
+pub struct TokenUserIndexes<'a> {
+    pub user: MultiIndex<'a, Addr, TokenUser, String>,
+}
+
+impl<'a> IndexList<TokenUser> for TokenUserIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenUser>> + '_> {
+        let v: Vec<&dyn Index<TokenUser>> = vec![&self.user];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn token_users<'a>() -> IndexedMap<'a, &'a str, TokenUser, TokenUserIndexes<'a>> {
+    let indexes = TokenUserIndexes {
+        user: MultiIndex::new(|_pk, entry| entry.user.clone(), "token_users", "token_users__user"),
+    };
+    IndexedMap::new("token_users", indexes)
+}
+
+// Admin and emergency controls
+pub const ADMIN: Admin = Admin::new("admin");
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+// Curve revenue is the part of each mint price not kept as reserve, held in whatever denom it was paid in
+pub const PAYEES: Item<Vec<Payee>> = Item::new("payees");
+// (payee, denom) -> amount owed
+pub const CLAIMABLE: Map<(&Addr, &str), Uint128> = Map::new("claimable");
+// Denom -> revenue credited to payees and not yet paid out
+pub const UNCLAIMED_REVENUE: Map<&str, Uint128> = Map::new("unclaimed_revenue");
+pub const PLATFORM_FEE: Item<PlatformFee> = Item::new("platform_fee");
+
+// Multi-denom pricing, unset means curve prices are paid in the curve denom only
+pub const PRICING: Item<PricingConfig> = Item::new("pricing");
+// Denom -> last oracle rate a mint or sell-back accepted, the reference for the deviation check
+pub const LAST_RATES: Map<&str, ExchangeRateResponse> = Map::new("last_rates");
+
+// Holder governance. Weights are checkpointed every block they change so proposals read them as of their creation
+pub const VOTING_WEIGHTS: SnapshotMap<&Addr, u64> = SnapshotMap::new(
+    "voting_weights",
+    "voting_weights__checkpoints",
+    "voting_weights__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_VOTING_WEIGHT: SnapshotItem<u64> = SnapshotItem::new(
+    "total_voting_weight",
+    "total_voting_weight__checkpoints",
+    "total_voting_weight__changelog",
+    Strategy::EveryBlock,
+);
+pub const GOVERNANCE_CONFIG: Item<GovernanceConfig> = Item::new("governance_config");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+// (proposal id, voter) -> how they voted
+pub const VOTES: Map<(u64, &Addr), VoteOption> = Map::new("votes");
+
+// Address that sent each token into a contract with SendNft; the contract may always hand it back,
+// even if the token was made soulbound while held there
+pub const ESCROW_SENDERS: Map<&str, Addr> = Map::new("escrow_senders");
+
+// Collections ics721 mints vouchers in, and the tokens minted by the cw721 minter, vouchers among them,
+// which hold no slot in the curve or contribution supply
+pub const VOUCHER_COLLECTION: Item<bool> = Item::new("voucher_collection");
+pub const MINTER_TOKENS: Map<&str, bool> = Map::new("minter_tokens");
+
+// Collection metadata
+pub const METADATA_CONFIG: Item<MetadataConfig> = Item::new("metadata_config");
+pub const COLLECTION_ALBUM: Item<AlbumMetadata> = Item::new("collection_album");
+
+// 0.1.x layouts, only read by migrations
+const LEGACY_CURVE_MINTED: Map<&str, u32> = Map::new("curve_minted");
+const LEGACY_CONTRIBUTION_MINTED: Map<&str, u32> = Map::new("contribution_minted");
+const LEGACY_CONTRIBUTION_PROOFS: Map<&str, bool> = Map::new("contribution_proofs");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Initialize curve state
+    CURVE_MINTED.save(deps.storage, &0)?;
+    CURVE_TOKEN_SEQ.save(deps.storage, &0)?;
+    CURVE_RESERVE.save(deps.storage, &Uint128::zero())?;
+    let phases = msg.phases.unwrap_or_default();
+    validate_phases(&phases)?;
+    MINT_PHASES.save(deps.storage, &phases)?;
+    AUCTION_ID.save(deps.storage, &0)?;
+    AUCTION_ESCROW.save(deps.storage, &Uint128::zero())?;
+    save_sale_mode(deps.storage, &msg.sale_mode.unwrap_or(SaleMode::BondingCurve {}))?;
+    CONTRIBUTION_MINTED.save(deps.storage, &0)?;
+    CONTRIBUTION_TOKEN_SEQ.save(deps.storage, &0)?;
+    SUPPLY_CONFIG.save(deps.storage, &msg.supply.unwrap_or(SupplyConfig {
+        curve_supply: CURVE_SUPPLY,
+        contribution_supply: CONTRIBUTION_SUPPLY,
+    }))?;
+    PAUSED.save(deps.storage, &false)?;
+    SOULBOUND_CONTRIBUTIONS.save(deps.storage, &msg.soulbound_contributions.unwrap_or(false))?;
+    let governance = msg.governance.unwrap_or_default();
+    governance.validate()?;
+    GOVERNANCE_CONFIG.save(deps.storage, &governance)?;
+    PROPOSAL_COUNT.save(deps.storage, &0)?;
+
+    // Voucher collections only hold what the bridge mints, so they have no curve to validate
+    let voucher_collection = msg.curve_denom.is_empty();
+    VOUCHER_COLLECTION.save(deps.storage, &voucher_collection)?;
+    let curve_config = CurveConfig {
+        curve_type: msg.curve_type.unwrap_or(CurveType::Linear {}),
+        start_price: msg.curve_start_price,
+        rate: msg.curve_rate,
+        denom: msg.curve_denom,
+        sell_spread_bps: msg.sell_spread_bps.unwrap_or(DEFAULT_SELL_SPREAD_BPS),
+    };
+    if !voucher_collection {
+        curve_config.validate()?;
+    }
+    CURVE_CONFIG.save(deps.storage, &curve_config)?;
+
+    let admin = deps.api.addr_validate(msg.admin.as_deref().unwrap_or(&msg.minter))?;
+    let payees = msg.payees.unwrap_or_else(|| vec![Payee {
+        address: admin.to_string(),
+        weight: 1,
+    }]);
+    validate_payees(deps.api, &payees)?;
+    PAYEES.save(deps.storage, &payees)?;
+    if let Some(pricing) = msg.pricing {
+        pricing.validate(deps.api)?;
+        PRICING.save(deps.storage, &pricing)?;
+    }
+    if let Some(platform_fee) = msg.platform_fee {
+        platform_fee.validate(deps.api)?;
+        PLATFORM_FEE.save(deps.storage, &platform_fee)?;
+    }
+    ADMIN.set(deps.branch(), Some(admin))?;
+    if let Some(royalty) = msg.royalty {
+        royalty.validate(deps.api)?;
+        DEFAULT_ROYALTY.save(deps.storage, &royalty)?;
+    }
+    if let Some(root) = msg.contribution_root {
+        validate_merkle_root(&root)?;
+        CONTRIBUTION_ROOT.save(deps.storage, &root)?;
+    }
+    METADATA_CONFIG.save(deps.storage, &MetadataConfig {
+        base_uri: msg.base_uri,
+        frozen: false,
+    })?;
+    if let Some(album) = msg.album {
+        album.validate(deps.api)?;
+        COLLECTION_ALBUM.save(deps.storage, &album)?;
+    }
+
+    // Initialize cw721 base contract
+    let cw721_msg = Cw721InstantiateMsg {
+        name: msg.name,
+        symbol: msg.symbol,
+        minter: msg.minter,
+    };
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    cw721_contract.instantiate(deps, env, info, cw721_msg)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("curve_start_price", msg.curve_start_price)
+        .add_attribute("curve_rate", msg.curve_rate))
+}
+
+type MigrationStep = fn(DepsMut, &Env, &MigrateMsg) -> Result<(), ContractError>;
+
+// Ordered state migrations, one per schema change, each runs when the stored version is older than its target
+const MIGRATIONS: &[(&str, &str, MigrationStep)] = &[
+    ("0.2.0", "counters", migrate_counters),
+    ("0.2.0", "curve", migrate_curve),
+    ("0.2.0", "admin", migrate_admin),
+    ("0.2.0", "payees", migrate_payees),
+    ("0.2.0", "metadata", migrate_metadata),
+    ("0.2.0", "contribution_proofs", migrate_contribution_proofs),
+    ("0.2.0", "sale", migrate_sale),
+    ("0.2.0", "tier_index", migrate_tier_index),
+    ("0.2.0", "soulbound", migrate_soulbound),
+    ("0.2.0", "governance", migrate_governance),
+];
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidMigration {
+            reason: format!("cannot migrate from {}", stored.contract),
+        });
+    }
+
+    let stored_version: Version = stored.version.parse()?;
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    if stored_version > new_version {
+        return Err(ContractError::InvalidMigration {
+            reason: format!("cannot downgrade from {} to {}", stored_version, new_version),
+        });
+    }
+
+    let mut response = Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION);
+
+    for (target, name, step) in MIGRATIONS {
+        if stored_version < target.parse()? {
+            step(deps.branch(), &env, &msg)?;
+            response = response.add_attribute("migration", format!("{}:{}", target, name));
+        }
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(response)
+}
+
+// 0.1.x kept counters under a "total" map key and had no token sequences
+fn migrate_counters(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    let curve_minted = LEGACY_CURVE_MINTED.may_load(deps.storage, "total")?.unwrap_or_default();
+    let contribution_minted = LEGACY_CONTRIBUTION_MINTED
+        .may_load(deps.storage, "total")?
+        .unwrap_or_default();
+    CURVE_MINTED.save(deps.storage, &curve_minted)?;
+    CONTRIBUTION_MINTED.save(deps.storage, &contribution_minted)?;
+    LEGACY_CURVE_MINTED.remove(deps.storage, "total");
+    LEGACY_CONTRIBUTION_MINTED.remove(deps.storage, "total");
+
+    // Nothing was ever burned in 0.1.x, so ids continue from the minted count
+    if !CURVE_TOKEN_SEQ.exists(deps.storage) {
+        CURVE_TOKEN_SEQ.save(deps.storage, &curve_minted)?;
+    }
+    if !CONTRIBUTION_TOKEN_SEQ.exists(deps.storage) {
+        CONTRIBUTION_TOKEN_SEQ.save(deps.storage, &contribution_minted)?;
+    }
+    Ok(())
+}
+
+// Curve config and reserve, with the prices 0.1.x hard-coded
+fn migrate_curve(deps: DepsMut, _env: &Env, msg: &MigrateMsg) -> Result<(), ContractError> {
+    if !CURVE_RESERVE.exists(deps.storage) {
+        CURVE_RESERVE.save(deps.storage, &Uint128::zero())?;
+    }
+    if !CURVE_CONFIG.exists(deps.storage) {
+        let curve_denom = msg.curve_denom.clone().ok_or(ContractError::InvalidMigration {
+            reason: "curve_denom is required when migrating from 0.1.x".to_string(),
+        })?;
+        let curve_config = CurveConfig {
+            curve_type: CurveType::Linear {},
+            start_price: Uint128::from(1000000u128),
+            rate: Uint128::from(50000u128),
+            denom: curve_denom,
+            sell_spread_bps: DEFAULT_SELL_SPREAD_BPS,
+        };
+        curve_config.validate()?;
+        CURVE_CONFIG.save(deps.storage, &curve_config)?;
+    }
+    Ok(())
+}
+
+// Admin, pause switch and supply caps
+fn migrate_admin(mut deps: DepsMut, _env: &Env, msg: &MigrateMsg) -> Result<(), ContractError> {
+    if !matches!(ADMIN.get(deps.as_ref()), Ok(Some(_))) {
+        let admin = msg.admin.as_deref().ok_or(ContractError::InvalidMigration {
+            reason: "admin is required when migrating from 0.1.x".to_string(),
+        })?;
+        let admin = deps.api.addr_validate(admin)?;
+        ADMIN.set(deps.branch(), Some(admin))?;
+    }
+    if !PAUSED.exists(deps.storage) {
+        PAUSED.save(deps.storage, &false)?;
+    }
+    if !SUPPLY_CONFIG.exists(deps.storage) {
+        SUPPLY_CONFIG.save(deps.storage, &SupplyConfig {
+            curve_supply: CURVE_SUPPLY,
+            contribution_supply: CONTRIBUTION_SUPPLY,
+        })?;
+    }
+    Ok(())
+}
+
+// Curve revenue goes to the admin until payees are set
+fn migrate_payees(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    if !PAYEES.exists(deps.storage) {
+        let admin = ADMIN.get(deps.as_ref())?.ok_or(ContractError::InvalidMigration {
+            reason: "payees need an admin to default to".to_string(),
+        })?;
+        PAYEES.save(deps.storage, &vec![Payee {
+            address: admin.to_string(),
+            weight: 1,
+        }])?;
+    }
+    Ok(())
+}
+
+fn migrate_metadata(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    if !METADATA_CONFIG.exists(deps.storage) {
+        METADATA_CONFIG.save(deps.storage, &MetadataConfig {
+            base_uri: None,
+            frozen: false,
+        })?;
+    }
+    Ok(())
+}
+
+// Claims were keyed by the Debug-formatted proof, which said nothing about who claimed
+fn migrate_contribution_proofs(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    let legacy_proofs = LEGACY_CONTRIBUTION_PROOFS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for proof in legacy_proofs {
+        LEGACY_CONTRIBUTION_PROOFS.remove(deps.storage, &proof);
+    }
+    Ok(())
+}
+
+// Mint phases and the sale mode, curve mints stay open to everyone on the bonding curve
+fn migrate_sale(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    if !MINT_PHASES.exists(deps.storage) {
+        MINT_PHASES.save(deps.storage, &vec![])?;
+    }
+    if !SALE_MODE.exists(deps.storage) {
+        SALE_MODE.save(deps.storage, &SaleMode::BondingCurve {})?;
+        AUCTION_ID.save(deps.storage, &0)?;
+        AUCTION_ESCROW.save(deps.storage, &Uint128::zero())?;
+    }
+    Ok(())
+}
+
+// 0.1.x had no tier index, rebuild it from the minted tokens
+fn migrate_tier_index(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    for (token_id, _, tier) in tiered_tokens(deps.storage)? {
+        index_token_tier(deps.storage, &token_id, None, Some(&tier))?;
+    }
+    Ok(())
+}
+
+fn migrate_soulbound(deps: DepsMut, _env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    if !SOULBOUND_CONTRIBUTIONS.exists(deps.storage) {
+        SOULBOUND_CONTRIBUTIONS.save(deps.storage, &false)?;
+    }
+    Ok(())
+}
+
+// Voting rules, and weights backfilled from the tiers holders already have
+fn migrate_governance(deps: DepsMut, env: &Env, _msg: &MigrateMsg) -> Result<(), ContractError> {
+    if GOVERNANCE_CONFIG.exists(deps.storage) {
+        return Ok(());
+    }
+    GOVERNANCE_CONFIG.save(deps.storage, &GovernanceConfig::default())?;
+    PROPOSAL_COUNT.save(deps.storage, &0)?;
+    for (_, owner, tier) in tiered_tokens(deps.storage)? {
+        shift_voting_weight(deps.storage, env.block.height, None, Some(&owner), tier_weight(Some(&tier)))?;
+    }
+    Ok(())
+}
+
+fn tiered_tokens(storage: &dyn Storage) -> StdResult<Vec<(String, Addr, String)>> {
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    cw721_contract
+        .tokens
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((token_id, token)) => token.extension.tier.map(|tier| Ok((token_id, token.owner, tier))),
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::MintCurve { allowlist_proof } => execute_mint_curve(deps, env, info, allowlist_proof),
+        ExecuteMsg::MintCurveBatch { count, max_total_price, allowlist_proof } => {
+            execute_mint_curve_batch(deps, env, info, count, max_total_price, allowlist_proof)
+        }
+        ExecuteMsg::MintContribution { proof, contribution_points } => {
+            execute_mint_contribution(deps, env, info, proof, contribution_points)
+        }
+        ExecuteMsg::MintContributionSigned { points, nonce, expires, signature } => {
+            execute_mint_contribution_signed(deps, env, info, points, nonce, expires, signature)
+        }
+        ExecuteMsg::UpgradeTier { token_id, proof, new_points } => {
+            execute_upgrade_tier(deps, env, info, token_id, proof, new_points)
+        }
+        ExecuteMsg::UpgradeTierSigned { token_id, points, nonce, expires, signature } => {
+            execute_upgrade_tier_signed(deps, env, info, token_id, points, nonce, expires, signature)
+        }
+        ExecuteMsg::SetContributionRoot { root } => execute_set_contribution_root(deps, info, root),
+        ExecuteMsg::UpdateSigners { add, remove } => execute_update_signers(deps, info, add, remove),
+        ExecuteMsg::SellCurve { token_id } => execute_sell_curve(deps, env, info, token_id),
+        ExecuteMsg::SettleAuction {} => execute_settle_auction(deps, env, info),
+        ExecuteMsg::ClaimRebate { auction_id } => execute_claim_rebate(deps, info, auction_id),
+        ExecuteMsg::SetTokenRoyalty { token_id, royalty } => {
+            execute_set_token_royalty(deps, info, token_id, royalty)
+        }
+        ExecuteMsg::SetSoulbound { token_id, soulbound } => {
+            execute_set_soulbound(deps, info, token_id, soulbound)
+        }
+        ExecuteMsg::SetLicenseScope { token_id, album_id } => {
+            execute_set_license_scope(deps, info, token_id, album_id)
+        }
+        ExecuteMsg::SetUser { token_id, user, expires } => {
+            execute_set_user(deps, env, info, token_id, user, expires)
+        }
+        ExecuteMsg::RecoverToken { token_id, recipient } => {
+            execute_recover_token(deps, env, info, token_id, recipient)
+        }
+        ExecuteMsg::UpdateConfig(update) => execute_update_config(deps, info, update),
+        ExecuteMsg::Pause {} => execute_set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => execute_set_paused(deps, info, false),
+        ExecuteMsg::TransferAdmin { new_admin } => {
+            let new_admin = deps.api.addr_validate(&new_admin)?;
+            let event = config_update_event(&info.sender, &["admin"]);
+            Ok(ADMIN.execute_update_admin(deps, info, Some(new_admin))?.add_event(event))
+        }
+        ExecuteMsg::UpdateMetadata { token_id, album } => {
+            execute_update_metadata(deps, info, token_id, album)
+        }
+        ExecuteMsg::FreezeMetadata {} => execute_freeze_metadata(deps, info),
+        ExecuteMsg::SetClassData { class_data } => execute_set_class_data(deps, info, class_data),
+        ExecuteMsg::Withdraw {} => execute_withdraw(deps, info),
+        ExecuteMsg::DistributeProceeds {} => execute_distribute_proceeds(deps),
+        ExecuteMsg::Propose { title, description, action } => {
+            execute_propose(deps, env, info, title, description, action)
+        }
+        ExecuteMsg::Vote { proposal_id, vote } => execute_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::CloseProposal { proposal_id } => execute_close_proposal(deps, env, proposal_id),
+        ExecuteMsg::Mint { token_id, owner, token_uri, extension } => {
+            execute_mint(deps, env, info, token_id, owner, token_uri, extension)
+        }
+        ExecuteMsg::Burn { token_id } => execute_burn(deps, env, info, token_id),
+        // Delegate other messages to cw721 base
+        _ => {
+            // Tokens stay where they are while paused, including on their way to or from the bridge
+            if matches!(msg, ExecuteMsg::TransferNft { .. } | ExecuteMsg::SendNft { .. }) {
+                assert_not_paused(deps.as_ref())?;
+            }
+            let returning = match &msg {
+                ExecuteMsg::TransferNft { recipient, token_id } => ESCROW_SENDERS
+                    .may_load(deps.storage, token_id)?
+                    .is_some_and(|sender| sender.as_str() == recipient),
+                _ => false,
+            };
+            if let ExecuteMsg::TransferNft { token_id, .. }
+            | ExecuteMsg::SendNft { token_id, .. }
+            | ExecuteMsg::Approve { token_id, .. } = &msg
+            {
+                if !returning {
+                    assert_not_soulbound(deps.as_ref(), token_id)?;
+                }
+            }
+            let transfer = match &msg {
+                ExecuteMsg::TransferNft { recipient, token_id } => {
+                    Some((token_id.clone(), recipient.clone(), "transfer"))
+                }
+                ExecuteMsg::SendNft { contract, token_id, .. } => {
+                    Some((token_id.clone(), contract.clone(), "send"))
+                }
+                _ => None,
+            };
+            let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+            let from = match &transfer {
+                Some((token_id, to, kind)) => {
+                    token_users().remove(deps.storage, token_id)?;
+                    match cw721_contract.tokens.may_load(deps.storage, token_id)? {
+                        Some(token) => {
+                            // cw721 reverts this along with the transfer if the sender may not make it
+                            let to = deps.api.addr_validate(to)?;
+                            let weight = token_weight(deps.storage, token_id, &token.extension)?;
+                            shift_voting_weight(deps.storage, env.block.height, Some(&token.owner), Some(&to), weight)?;
+                            if *kind == "send" {
+                                ESCROW_SENDERS.save(deps.storage, token_id, &token.owner)?;
+                            } else {
+                                ESCROW_SENDERS.remove(deps.storage, token_id);
+                            }
+                            Some(token.owner)
+                        }
+                        None => None,
+                    }
+                }
+                None => None,
+            };
+
+            let response = cw721_contract.execute(deps, env, info, msg.try_into()?)?;
+            Ok(match (transfer, from) {
+                (Some((token_id, to, kind)), Some(from)) => {
+                    response.add_event(transfer_event(&token_id, from.as_str(), &to, kind))
+                }
+                _ => response,
+            })
+        }
+    }
+}
+
+pub fn execute_mint_curve(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    allowlist_proof: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    assert_native_collection(deps.as_ref())?;
+
+    let current_curve_minted = CURVE_MINTED.load(deps.storage)?;
+    
+    if current_curve_minted >= SUPPLY_CONFIG.load(deps.storage)?.curve_supply {
+        return Err(ContractError::CurveSupplyExhausted {});
+    }
+
+    let phase = check_mint_phase(deps.as_ref(), &env, &info.sender, allowlist_proof, 1)?;
+
+    // Calculate price based on the sale mode, unless the phase fixes it
+    let curve_config = CURVE_CONFIG.load(deps.storage)?;
+    let sale_mode = SALE_MODE.load(deps.storage)?;
+    let proceeds = mint_proceeds(deps.storage, &env, &sale_mode, phase.as_ref())?;
+    let price = quote_mint(&curve_config, &sale_mode, phase.as_ref(), current_curve_minted, 1, env.block.time)?
+        .total_price;
+    
+    // Verify payment in the denom sent, anything above the converted price goes back to the sender
+    let quote = payment_quote(deps.branch(), &env, &curve_config, &info)?;
+    let paid = quote.amount(price)?;
+    let refund = collect_payment(&info, &quote.denom, paid)?;
+
+    let (token_id, minted) =
+        mint_curve_token(deps.branch(), &env, &info, &curve_config, price, &quote, &proceeds)?;
+    if let Some(phase) = phase {
+        record_phase_mints(deps.storage, &phase.name, &info.sender, 1)?;
+    }
+
+    let response = minted
+        .add_attribute("method", "mint_curve")
+        .add_attribute("token_id", &token_id)
+        .add_attribute("price", paid)
+        .add_event(curve_mint_event(&token_id, &info.sender, paid, &quote.denom));
+
+    Ok(with_refund(response, &info.sender, refund, &quote.denom))
+}
+
+pub fn execute_mint_curve_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    count: u32,
+    max_total_price: Uint128,
+    allowlist_proof: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    assert_native_collection(deps.as_ref())?;
+
+    if count == 0 || count > MAX_BATCH_MINT {
+        return Err(ContractError::InvalidBatchSize { max: MAX_BATCH_MINT });
+    }
+
+    let current_curve_minted = CURVE_MINTED.load(deps.storage)?;
+    if current_curve_minted + count > SUPPLY_CONFIG.load(deps.storage)?.curve_supply {
+        return Err(ContractError::CurveSupplyExhausted {});
+    }
+
+    let phase = check_mint_phase(deps.as_ref(), &env, &info.sender, allowlist_proof, count)?;
+
+    // Each token pays its own point on the curve, not count * current price
+    let curve_config = CURVE_CONFIG.load(deps.storage)?;
+    let sale_mode = SALE_MODE.load(deps.storage)?;
+    let proceeds = mint_proceeds(deps.storage, &env, &sale_mode, phase.as_ref())?;
+    let simulation = quote_mint(
+        &curve_config,
+        &sale_mode,
+        phase.as_ref(),
+        current_curve_minted,
+        count,
+        env.block.time,
+    )?;
+    if simulation.total_price > max_total_price {
+        return Err(ContractError::SlippageExceeded {
+            total_price: simulation.total_price,
+            max_total_price,
+        });
+    }
+
+    // Each token is converted on its own so the total matches what gets credited
+    let quote = payment_quote(deps.branch(), &env, &curve_config, &info)?;
+    let amounts = simulation
+        .prices
+        .iter()
+        .map(|price| quote.amount(*price))
+        .collect::<Result<Vec<_>, _>>()?;
+    let paid = amounts
+        .iter()
+        .try_fold(Uint128::zero(), |total, amount| total.checked_add(*amount))
+        .map_err(StdError::from)?;
+    let refund = collect_payment(&info, &quote.denom, paid)?;
+
+    // cw721's attributes are kept for every token minted
+    let mut response = Response::new();
+    let mut token_ids = Vec::with_capacity(simulation.prices.len());
+    for price in &simulation.prices {
+        let (token_id, minted) =
+            mint_curve_token(deps.branch(), &env, &info, &curve_config, *price, &quote, &proceeds)?;
+        response = response.add_attributes(minted.attributes);
+        token_ids.push(token_id);
+    }
+    if let Some(phase) = phase {
+        record_phase_mints(deps.storage, &phase.name, &info.sender, count)?;
+    }
+
+    let events = token_ids
+        .iter()
+        .zip(&amounts)
+        .map(|(token_id, amount)| curve_mint_event(token_id, &info.sender, *amount, &quote.denom));
+    let response = response
+        .add_attribute("method", "mint_curve_batch")
+        .add_attribute("count", count.to_string())
+        .add_attribute("token_ids", token_ids.join(","))
+        .add_attribute("total_price", paid)
+        .add_events(events);
+
+    Ok(with_refund(response, &info.sender, refund, &quote.denom))
+}
+
+// Mints the next curve token to the sender once `price`, converted by `quote`, has been collected,
+// returning its id and cw721's response
+fn mint_curve_token(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    curve_config: &CurveConfig,
+    price: Uint128,
+    quote: &PaymentQuote,
+    proceeds: &Proceeds,
+) -> Result<(String, Response), ContractError> {
+    let current_curve_minted = CURVE_MINTED.load(deps.storage)?;
+    let paid = quote.amount(price)?;
+
+    // Mint NFT
+    let token_seq = CURVE_TOKEN_SEQ.load(deps.storage)? + 1;
+    let token_id = format!("curve-{}", token_seq);
+    
+    let album = COLLECTION_ALBUM.may_load(deps.storage)?;
+    let token_info = TokenInfo {
+        owner: deps.api.addr_validate(&info.sender.to_string())?,
+        approvals: vec![],
+        token_uri: render_token_uri(deps.storage, &token_id, None)?,
+        extension: ContractExtension {
+            album_id: album.as_ref().map(|album| album.album_id.clone()),
+            album,
+            ..ContractExtension::default()
+        },
+    };
+
+    // Update storage
+    CURVE_MINTED.save(deps.storage, &(current_curve_minted + 1))?;
+    CURVE_TOKEN_SEQ.save(deps.storage, &token_seq)?;
+
+    match proceeds {
+        Proceeds::Reserve if quote.denom == curve_config.denom => {
+            // Back the sell price of this supply slot so the token can always be redeemed,
+            // as far as a discounted phase price allows
+            let reserve_share = quote
+                .amount(curve_config.sell_price(curve_config.price_at(current_curve_minted)?))?
+                .min(paid);
+            CURVE_RESERVE.update(deps.storage, |reserve| -> StdResult<_> {
+                Ok(reserve.checked_add(reserve_share)?)
+            })?;
+            if !reserve_share.is_zero() {
+                CURVE_BACKING.save(deps.storage, &token_id, &reserve_share)?;
+            }
+            credit_revenue(deps.storage, paid - reserve_share, &quote.denom)?;
+        }
+        // The reserve is only held in the curve denom, so other denoms back nothing, like a free phase mint
+        Proceeds::Reserve | Proceeds::Revenue => credit_revenue(deps.storage, paid, &quote.denom)?,
+        // Rebates are the difference between prices paid, which only holds when nothing is converted
+        Proceeds::Auction { rebate: true, .. } if quote.rate.is_some() => {
+            return Err(ContractError::RebateDenom {
+                denom: curve_config.denom.clone(),
+            });
+        }
+        Proceeds::Auction { auction_id, rebate } => {
+            AUCTIONS.update(deps.storage, *auction_id, |state| -> StdResult<_> {
+                let mut state = state.unwrap_or_default();
+                state.sold += 1;
+                state.proceeds = state.proceeds.checked_add(price)?;
+                state.last_price = Some(price);
+                Ok(state)
+            })?;
+            if *rebate {
+                AUCTION_PURCHASES.update(deps.storage, (*auction_id, &info.sender), |purchase| -> StdResult<_> {
+                    let purchase = purchase.unwrap_or(AuctionPurchase {
+                        count: 0,
+                        paid: Uint128::zero(),
+                    });
+                    Ok(AuctionPurchase {
+                        count: purchase.count + 1,
+                        paid: purchase.paid.checked_add(price)?,
+                    })
+                })?;
+                AUCTION_ESCROW.update(deps.storage, |escrow| -> StdResult<_> {
+                    Ok(escrow.checked_add(price)?)
+                })?;
+            } else {
+                credit_revenue(deps.storage, paid, &quote.denom)?;
+            }
+        }
+    }
+    
+    // Mint via cw721 base
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let response = cw721_contract.mint(deps, env.clone(), info.clone(), token_id.clone(), token_info)?;
+
+    Ok((token_id, response))
+}
+
+// Returns the phase governing a curve mint of `count` tokens by `sender`, None when no phases are set
+fn check_mint_phase(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    allowlist_proof: Option<Vec<String>>,
+    count: u32,
+) -> Result<Option<MintPhase>, ContractError> {
+    let phases = MINT_PHASES.load(deps.storage)?;
+    if phases.is_empty() {
+        return Ok(None);
+    }
+
+    let phase = active_phase(phases, env.block.time).ok_or(ContractError::MintingClosed {})?;
+    match &phase.kind {
+        PhaseKind::Closed {} => return Err(ContractError::MintingClosed {}),
+        PhaseKind::Allowlist { merkle_root } => {
+            let proof = allowlist_proof.ok_or(ContractError::NotAllowlisted {})?;
+            let leaf = sha256(sender.as_bytes());
+            verify_merkle_proof(merkle_root, leaf, &proof).map_err(|_| ContractError::NotAllowlisted {})?;
+        }
+        PhaseKind::Public {} => {}
+    }
+
+    if let Some(max_per_address) = phase.max_per_address {
+        let minted = PHASE_MINTS
+            .may_load(deps.storage, (&phase.name, sender))?
+            .unwrap_or_default();
+        if minted + count > max_per_address {
+            return Err(ContractError::PhaseLimitReached {
+                phase: phase.name,
+                max_per_address,
+            });
+        }
+    }
+
+    Ok(Some(phase))
+}
+
+fn active_phase(phases: Vec<MintPhase>, now: Timestamp) -> Option<MintPhase> {
+    phases
+        .into_iter()
+        .find(|phase| phase.start <= now && now < phase.end)
+}
+
+// Where the price of a curve mint goes
+enum Proceeds {
+    // Sell price into the reserve, the rest to payees
+    Reserve,
+    // Straight to payees
+    Revenue,
+    // Counted towards the auction, escrowed until settlement when it pays rebates
+    Auction { auction_id: u64, rebate: bool },
+}
+
+fn mint_proceeds(
+    storage: &dyn Storage,
+    env: &Env,
+    sale_mode: &SaleMode,
+    phase: Option<&MintPhase>,
+) -> Result<Proceeds, ContractError> {
+    let auction = match sale_mode {
+        SaleMode::BondingCurve {} => return Ok(Proceeds::Reserve),
+        // A phase price replaces the auction price, so those mints take no part in it
+        SaleMode::DutchAuction(_) if phase.and_then(|phase| phase.price).is_some() => {
+            return Ok(Proceeds::Revenue)
+        }
+        SaleMode::DutchAuction(auction) => auction,
+    };
+
+    if env.block.time < auction.start_time {
+        return Err(ContractError::AuctionNotStarted {});
+    }
+    let auction_id = AUCTION_ID.load(storage)?;
+    if AUCTIONS.load(storage, auction_id)?.clearing_price.is_some() {
+        return Err(ContractError::AuctionSettled { auction_id });
+    }
+
+    Ok(Proceeds::Auction {
+        auction_id,
+        rebate: auction.rebate,
+    })
+}
+
+// Prices of the next `count` curve mints: a phase price wins, then the auction price, then the curve
+fn quote_mint(
+    curve_config: &CurveConfig,
+    sale_mode: &SaleMode,
+    phase: Option<&MintPhase>,
+    current_supply: u32,
+    count: u32,
+    now: Timestamp,
+) -> StdResult<SimulateMintResponse> {
+    let flat_price = match (phase.and_then(|phase| phase.price), sale_mode) {
+        (Some(price), _) => Some(price),
+        (None, SaleMode::DutchAuction(auction)) => Some(auction.price_at(now)),
+        (None, SaleMode::BondingCurve {}) => None,
+    };
+
+    match flat_price {
+        Some(price) => Ok(SimulateMintResponse {
+            total_price: price.checked_mul(Uint128::from(count))?,
+            prices: vec![price; count as usize],
+        }),
+        None => curve_config.simulate_mint(current_supply, count),
+    }
+}
+
+// Opens a new auction whenever one is configured, so rebates stay tied to the prices it charged
+fn save_sale_mode(storage: &mut dyn Storage, sale_mode: &SaleMode) -> Result<(), ContractError> {
+    if let SaleMode::DutchAuction(auction) = sale_mode {
+        auction.validate()?;
+        let auction_id = AUCTION_ID.load(storage)? + 1;
+        AUCTION_ID.save(storage, &auction_id)?;
+        AUCTIONS.save(storage, auction_id, &AuctionState::default())?;
+    }
+    SALE_MODE.save(storage, sale_mode)?;
+    Ok(())
+}
+
+fn record_phase_mints(storage: &mut dyn Storage, phase: &str, sender: &Addr, count: u32) -> StdResult<()> {
+    PHASE_MINTS.update(storage, (phase, sender), |minted| -> StdResult<_> {
+        Ok(minted.unwrap_or_default() + count)
+    })?;
+    Ok(())
+}
+
+fn validate_phases(phases: &[MintPhase]) -> Result<(), ContractError> {
+    for (i, phase) in phases.iter().enumerate() {
+        if phase.name.is_empty() || phases[..i].iter().any(|other| other.name == phase.name) {
+            return Err(ContractError::InvalidPhase {
+                reason: format!("phase names must be unique and non-empty: {:?}", phase.name),
+            });
+        }
+        if phase.start >= phase.end {
+            return Err(ContractError::InvalidPhase {
+                reason: format!("phase {} ends before it starts", phase.name),
+            });
+        }
+        if let PhaseKind::Allowlist { merkle_root } = &phase.kind {
+            validate_merkle_root(merkle_root)?;
+        }
+    }
+    Ok(())
+}
+
+fn with_refund(response: Response, recipient: &Addr, refund: Uint128, denom: &str) -> Response {
+    if refund.is_zero() {
+        return response;
+    }
+
+    response
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(refund.u128(), denom),
+        })
+        .add_attribute("refund", refund)
+}
+
+// How prices in the pricing base unit convert into the denom being paid
+struct PaymentQuote {
+    denom: String,
+    // None when the denom is the base unit itself
+    rate: Option<Decimal>,
+}
+
+impl PaymentQuote {
+    fn amount(&self, base_amount: Uint128) -> Result<Uint128, ContractError> {
+        match self.rate {
+            Some(rate) => base_amount.checked_mul_floor(rate).map_err(|_| ContractError::ConversionOverflow {
+                price: base_amount,
+                denom: self.denom.clone(),
+            }),
+            None => Ok(base_amount),
+        }
+    }
+}
+
+// Quotes the denom sent with a mint, the curve denom when nothing is sent.
+// Missing or extra coins are left for `collect_payment` to reject
+fn payment_quote(
+    deps: DepsMut,
+    env: &Env,
+    curve_config: &CurveConfig,
+    info: &MessageInfo,
+) -> Result<PaymentQuote, ContractError> {
+    let denom = match info.funds.as_slice() {
+        [payment] => payment.denom.clone(),
+        _ => curve_config.denom.clone(),
+    };
+    quote_denom(deps, env, curve_config, denom)
+}
+
+// Quotes `denom` and records the rate as the reference for the next deviation check
+fn quote_denom(
+    deps: DepsMut,
+    env: &Env,
+    curve_config: &CurveConfig,
+    denom: String,
+) -> Result<PaymentQuote, ContractError> {
+    let rate = oracle_rate(deps.as_ref(), env, curve_config, &denom)?;
+    if let Some(rate) = &rate {
+        LAST_RATES.save(deps.storage, &denom, rate)?;
+    }
+
+    Ok(PaymentQuote {
+        denom,
+        rate: rate.map(|rate| rate.rate),
+    })
+}
+
+// Oracle rate from the pricing base unit into an accepted `denom`, None when no conversion applies.
+// The rate has to be fresh and within the allowed move from the last rate accepted for the denom
+fn oracle_rate(
+    deps: Deps,
+    env: &Env,
+    curve_config: &CurveConfig,
+    denom: &str,
+) -> Result<Option<ExchangeRateResponse>, ContractError> {
+    let pricing = match PRICING.may_load(deps.storage)? {
+        Some(pricing) => pricing,
+        None if denom == curve_config.denom => return Ok(None),
+        None => {
+            return Err(ContractError::InvalidDenom {
+                expected: curve_config.denom.clone(),
+                received: denom.to_string(),
+            })
+        }
+    };
+    if denom != curve_config.denom && !pricing.denoms.iter().any(|accepted| accepted == denom) {
+        return Err(ContractError::InvalidDenom {
+            expected: [vec![curve_config.denom.clone()], pricing.denoms].concat().join(","),
+            received: denom.to_string(),
+        });
+    }
+    let base = pricing.base_unit.unwrap_or_else(|| curve_config.denom.clone());
+    if denom == base {
+        return Ok(None);
+    }
+
+    let price: ExchangeRateResponse = deps.querier.query_wasm_smart(
+        &pricing.oracle,
+        &OracleQueryMsg::ExchangeRate {
+            base,
+            quote: denom.to_string(),
+        },
+    )?;
+    if price.rate.is_zero() {
+        return Err(ContractError::InvalidOraclePrice { denom: denom.to_string() });
+    }
+    let now = env.block.time.seconds();
+    let age = now.saturating_sub(price.updated_at.seconds());
+    if age > pricing.max_staleness {
+        return Err(ContractError::StalePrice { denom: denom.to_string(), age });
+    }
+
+    // A reference older than the staleness window says nothing about the market anymore
+    if let Some(last) = LAST_RATES.may_load(deps.storage, denom)? {
+        if now.saturating_sub(last.updated_at.seconds()) <= pricing.max_staleness {
+            let deviation = if price.rate > last.rate {
+                price.rate - last.rate
+            } else {
+                last.rate - price.rate
+            };
+            if deviation > last.rate * Decimal::from_ratio(pricing.max_deviation_bps, BPS_DENOMINATOR) {
+                return Err(ContractError::PriceDeviation { denom: denom.to_string() });
+            }
+        }
+    }
+
+    Ok(Some(price))
+}
+
+// Takes `price` in `denom` from the attached funds and returns the overpayment to refund
+fn collect_payment(info: &MessageInfo, denom: &str, price: Uint128) -> Result<Uint128, ContractError> {
+    let payment = match info.funds.as_slice() {
+        [] if price.is_zero() => return Ok(Uint128::zero()),
+        [] => return Err(ContractError::NoFunds {}),
+        [payment] => payment,
+        _ => return Err(ContractError::MultipleDenoms {}),
+    };
+
+    if payment.denom != denom {
+        return Err(ContractError::InvalidDenom {
+            expected: denom.to_string(),
+            received: payment.denom.clone(),
+        });
+    }
+    if payment.amount < price {
+        return Err(ContractError::InsufficientPayment { required: price });
+    }
+
+    Ok(payment.amount - price)
+}
+
+pub fn execute_sell_curve(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    if !token_id.starts_with("curve-") {
+        return Err(ContractError::NotCurveToken { token_id });
+    }
+    // Auction mints are not backed by the reserve
+    if !matches!(SALE_MODE.load(deps.storage)?, SaleMode::BondingCurve {}) {
+        return Err(ContractError::SellBackDisabled {});
+    }
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let token = cw721_contract.tokens.load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Tokens that put nothing into the reserve, minted free, at auction or in another denom, cannot draw on it
+    if !CURVE_BACKING.has(deps.storage, &token_id) {
+        return Err(ContractError::NotBacked { token_id });
+    }
+
+    // The top supply slot's curve price less the spread, in the curve denom at the current rate,
+    // as far as the reserve covers it
+    let curve_config = CURVE_CONFIG.load(deps.storage)?;
+    let top_slot = CURVE_MINTED.load(deps.storage)?.saturating_sub(1);
+    let sell_price = curve_config.sell_price(curve_config.price_at(top_slot)?);
+    let quote = quote_denom(deps.branch(), &env, &curve_config, curve_config.denom.clone())?;
+    let reserve = CURVE_RESERVE.load(deps.storage)?;
+    let payout = quote.amount(sell_price)?.min(reserve);
+
+    CURVE_RESERVE.save(deps.storage, &(reserve - payout))?;
+    CURVE_BACKING.remove(deps.storage, &token_id);
+    release_supply_slot(deps.storage, &CURVE_MINTED)?;
+    count_royalty_override(deps.storage, token.extension.royalty.is_some(), false)?;
+    token_users().remove(deps.storage, &token_id)?;
+    ESCROW_SENDERS.remove(deps.storage, &token_id);
+
+    let response = cw721_contract.execute(
+        deps.branch(),
+        env,
+        info.clone(),
+        cw721_base::ExecuteMsg::Burn { token_id: token_id.clone() },
+    )?;
+
+    let mut response = response
+        .add_attribute("method", "sell_curve")
+        .add_attribute("token_id", &token_id)
+        .add_attribute("payout", payout)
+        .add_event(
+            burn_event(&token_id, &token.owner, "sell")
+                .add_attribute("payout", payout)
+                .add_attribute("denom", &curve_config.denom),
+        );
+    if !payout.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(payout.u128(), curve_config.denom),
+        });
+    }
+
+    Ok(response)
+}
+
+pub fn execute_settle_auction(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let auction = match SALE_MODE.load(deps.storage)? {
+        SaleMode::DutchAuction(auction) => auction,
+        SaleMode::BondingCurve {} => return Err(ContractError::NoAuction {}),
+    };
+    let auction_id = AUCTION_ID.load(deps.storage)?;
+    let mut state = AUCTIONS.load(deps.storage, auction_id)?;
+    if state.clearing_price.is_some() {
+        return Err(ContractError::AuctionSettled { auction_id });
+    }
+
+    // Before the floor or a sell-out only the admin can call the auction
+    let sold_out = CURVE_MINTED.load(deps.storage)? >= SUPPLY_CONFIG.load(deps.storage)?.curve_supply;
+    if env.block.time < auction.floor_reached_at() && !sold_out {
+        ADMIN
+            .assert_admin(deps.as_ref(), &info.sender)
+            .map_err(|_| ContractError::AuctionNotEnded {})?;
+    }
+
+    // Prices only go down, so the last sale is the lowest one
+    let clearing_price = state.last_price.unwrap_or_else(|| auction.price_at(env.block.time));
+    state.clearing_price = Some(clearing_price);
+    AUCTIONS.save(deps.storage, auction_id, &state)?;
+
+    if auction.rebate {
+        let revenue = clearing_price
+            .checked_mul(Uint128::from(state.sold))
+            .map_err(StdError::from)?;
+        AUCTION_ESCROW.update(deps.storage, |escrow| -> StdResult<_> {
+            Ok(escrow.checked_sub(revenue)?)
+        })?;
+        let denom = CURVE_CONFIG.load(deps.storage)?.denom;
+        credit_revenue(deps.storage, revenue, &denom)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "settle_auction")
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("clearing_price", clearing_price)
+        .add_attribute("sold", state.sold.to_string()))
+}
+
+pub fn execute_claim_rebate(deps: DepsMut, info: MessageInfo, auction_id: u64) -> Result<Response, ContractError> {
+    let clearing_price = AUCTIONS
+        .may_load(deps.storage, auction_id)?
+        .and_then(|state| state.clearing_price)
+        .ok_or(ContractError::AuctionNotSettled { auction_id })?;
+    let purchase = AUCTION_PURCHASES
+        .may_load(deps.storage, (auction_id, &info.sender))?
+        .ok_or(ContractError::NothingToClaim {})?;
+
+    // Nothing is owed to a buyer who paid no more than the clearing price
+    let rebate = purchase.paid.saturating_sub(
+        clearing_price
+            .checked_mul(Uint128::from(purchase.count))
+            .map_err(StdError::from)?,
+    );
+    if rebate.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    AUCTION_PURCHASES.remove(deps.storage, (auction_id, &info.sender));
+    AUCTION_ESCROW.update(deps.storage, |escrow| -> StdResult<_> {
+        Ok(escrow.checked_sub(rebate)?)
+    })?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(rebate.u128(), CURVE_CONFIG.load(deps.storage)?.denom),
+        })
+        .add_attribute("method", "claim_rebate")
+        .add_attribute("auction_id", auction_id.to_string())
+        .add_attribute("rebate", rebate))
+}
+
+pub fn execute_burn(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let token = cw721_contract
+        .tokens
+        .may_load(deps.storage, &token_id)?
+        .ok_or(ContractError::TokenNotFound { token_id: token_id.clone() })?;
+    // The minter can always burn what it minted, so ics721 retires vouchers whatever their origin flags say
+    let minted = MINTER_TOKENS.has(deps.storage, &token_id);
+    let minter_burn =
+        minted && cw721_contract.minter(deps.as_ref())?.minter.as_deref() == Some(info.sender.as_str());
+    // Operators may burn for the owner, but not a token the owner cannot give away
+    if token.extension.soulbound && token.owner != info.sender && !minter_burn {
+        return Err(ContractError::Soulbound { token_id });
+    }
+
+    // Burned curve tokens forfeit their backing, which leaves the reserve as revenue with the supply slot.
+    // Tokens minted by the minter never took a supply slot and carry no tier or weight
+    let weight = token_weight(deps.storage, &token_id, &token.extension)?;
+    if minted {
+        MINTER_TOKENS.remove(deps.storage, &token_id);
+    } else if token_id.starts_with("curve-") {
+        if let Some(backing) = CURVE_BACKING.may_load(deps.storage, &token_id)? {
+            let reserve = CURVE_RESERVE.load(deps.storage)?;
+            let released = backing.min(reserve);
+            CURVE_RESERVE.save(deps.storage, &(reserve - released))?;
+            CURVE_BACKING.remove(deps.storage, &token_id);
+            let denom = CURVE_CONFIG.load(deps.storage)?.denom;
+            credit_revenue(deps.storage, released, &denom)?;
+        }
+        release_supply_slot(deps.storage, &CURVE_MINTED)?;
+    } else if token_id.starts_with("contribution-") {
+        CONTRIBUTION_CLAIMANTS.remove(deps.storage, &token_id);
+        release_supply_slot(deps.storage, &CONTRIBUTION_MINTED)?;
+    }
+    if !minted {
+        index_token_tier(deps.storage, &token_id, token.extension.tier.as_deref(), None)?;
+    }
+    shift_voting_weight(deps.storage, env.block.height, Some(&token.owner), None, weight)?;
+    count_royalty_override(deps.storage, token.extension.royalty.is_some(), false)?;
+    token_users().remove(deps.storage, &token_id)?;
+    ESCROW_SENDERS.remove(deps.storage, &token_id);
+
+    if minter_burn {
+        cw721_contract.tokens.remove(deps.storage, &token_id)?;
+        cw721_contract.decrement_tokens(deps.storage)?;
+        return Ok(Response::new()
+            .add_attribute("action", "burn")
+            .add_attribute("method", "burn")
+            .add_attribute("token_id", &token_id)
+            .add_event(burn_event(&token_id, &token.owner, "burn")));
+    }
+
+    // cw721 checks the sender may burn and reverts all of the above otherwise
+    let response = cw721_contract.execute(
+        deps.branch(),
+        env,
+        info,
+        cw721_base::ExecuteMsg::Burn { token_id: token_id.clone() },
+    )?;
+
+    Ok(response
+        .add_attribute("method", "burn")
+        .add_attribute("token_id", &token_id)
+        .add_event(burn_event(&token_id, &token.owner, "burn")))
+}
+
+// Frees one slot of a supply counter, which cannot hold fewer tokens than are being removed
+fn release_supply_slot(storage: &mut dyn Storage, minted: &Item<u32>) -> Result<u32, ContractError> {
+    minted.update(storage, |minted| minted.checked_sub(1).ok_or(ContractError::MintedCountUnderflow {}))
+}
+
+pub fn execute_mint_contribution(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proof: Vec<String>,
+    contribution_points: Uint128,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    assert_native_collection(deps.as_ref())?;
+
+    // One contribution allocation per address, whatever proof ordering is submitted
+    if CONTRIBUTION_PROOFS.has(deps.storage, &info.sender) {
+        return Err(ContractError::ProofAlreadyUsed {});
+    }
+
+    let root = CONTRIBUTION_ROOT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ContributionRootNotSet {})?;
+    let leaf = verify_contribution_proof(&root, &info.sender, contribution_points, &proof)?;
+
+    let owner = info.sender.clone();
+    let (token_id, tier, minted) = mint_contribution_token(deps, env, info, contribution_points, leaf)?;
+
+    Ok(minted
+        .add_attribute("method", "mint_contribution")
+        .add_attribute("token_id", &token_id)
+        .add_attribute("tier", tier)
+        .add_attribute("contribution_points", contribution_points)
+        .add_event(contribution_mint_event(&token_id, &owner, tier, contribution_points)))
+}
+
+pub fn execute_mint_contribution_signed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    points: Uint128,
+    nonce: u64,
+    expires: u64,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    assert_native_collection(deps.as_ref())?;
+
+    // The first attestation mints, later ones raise the token's tier through UpgradeTierSigned
+    if CONTRIBUTION_PROOFS.has(deps.storage, &info.sender) {
+        return Err(ContractError::ProofAlreadyUsed {});
+    }
+    let (signer, attestation) =
+        verify_attestation(deps.branch(), &env, &info.sender, points, nonce, expires, &signature)?;
+
+    let owner = info.sender.clone();
+    let (token_id, tier, minted) =
+        mint_contribution_token(deps, env, info, points, hex::encode(attestation))?;
+
+    Ok(minted
+        .add_attribute("method", "mint_contribution_signed")
+        .add_attribute("token_id", &token_id)
+        .add_attribute("tier", tier)
+        .add_attribute("contribution_points", points)
+        .add_attribute("signer", signer.pubkey.to_base64())
+        .add_attribute("nonce", nonce.to_string())
+        .add_event(contribution_mint_event(&token_id, &owner, tier, points)))
+}
+
+// Mints the next contribution token once the sender's allocation has been verified,
+// returning its id, its tier and cw721's response
+fn mint_contribution_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contribution_points: Uint128,
+    claim: String,
+) -> Result<(String, &'static str, Response), ContractError> {
+    let current_contribution_minted = CONTRIBUTION_MINTED.load(deps.storage)?;
+    
+    if current_contribution_minted >= SUPPLY_CONFIG.load(deps.storage)?.contribution_supply {
+        return Err(ContractError::ContributionSupplyExhausted {});
+    }
+
+    // Check minimum contribution points (e.g., 100 points for Bronze tier)
+    if contribution_points < Uint128::from(100u128) {
+        return Err(ContractError::InsufficientContributionPoints { 
+            required: Uint128::from(100u128),
+            provided: contribution_points 
+        });
+    }
+
+    // Mark allocation as claimed
+    CONTRIBUTION_PROOFS.save(deps.storage, &info.sender, &claim)?;
+
+    // Determine tier based on contribution points
+    let tier = tier_for_points(contribution_points);
+
+    // Mint NFT
+    let token_seq = CONTRIBUTION_TOKEN_SEQ.load(deps.storage)? + 1;
+    let token_id = format!("contribution-{}", token_seq);
+    
+    let album = COLLECTION_ALBUM.may_load(deps.storage)?;
+    let token_info = TokenInfo {
+        owner: deps.api.addr_validate(&info.sender.to_string())?,
+        approvals: vec![],
+        token_uri: render_token_uri(deps.storage, &token_id, Some(tier))?,
+        extension: ContractExtension {
+            tier: Some(tier.to_string()),
+            contribution_points: Some(contribution_points),
+            album_id: album.as_ref().map(|album| album.album_id.clone()),
+            album,
+            soulbound: SOULBOUND_CONTRIBUTIONS.load(deps.storage)?,
+            ..ContractExtension::default()
+        },
+    };
+
+    // Update storage
+    CONTRIBUTION_MINTED.save(deps.storage, &(current_contribution_minted + 1))?;
+    CONTRIBUTION_TOKEN_SEQ.save(deps.storage, &token_seq)?;
+    CONTRIBUTION_CLAIMANTS.save(deps.storage, &token_id, &info.sender)?;
+    index_token_tier(deps.storage, &token_id, None, Some(tier))?;
+    shift_voting_weight(deps.storage, env.block.height, None, Some(&info.sender), tier_weight(Some(tier)))?;
+    
+    // Mint via cw721 base
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let response = cw721_contract.mint(deps, env, info, token_id.clone(), token_info)?;
+
+    Ok((token_id, tier, response))
+}
+
+/// Digest an attestation signer signs for `sender` to mint with `points`.
+///
+/// It is `sha256("digm-contribution:{contract}:{sender}:{points}:{nonce}:{expires}")`.
+/// Secp256k1 signers sign the digest directly, ed25519 signers sign its 32 bytes as the message.
+fn attestation_hash(contract: &Addr, sender: &Addr, points: Uint128, nonce: u64, expires: u64) -> [u8; 32] {
+    sha256(format!("digm-contribution:{}:{}:{}:{}:{}", contract, sender, points, nonce, expires).as_bytes())
+}
+
+// Checks an attestation of `points` for `sender` and spends its nonce, returning the signer and the digest
+fn verify_attestation(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    points: Uint128,
+    nonce: u64,
+    expires: u64,
+    signature: &Binary,
+) -> Result<(Signer, [u8; 32]), ContractError> {
+    if env.block.time.seconds() >= expires {
+        return Err(ContractError::AttestationExpired {});
+    }
+
+    let attestation = attestation_hash(&env.contract.address, sender, points, nonce, expires);
+    let signer = find_attestation_signer(deps.as_ref(), &attestation, signature)?;
+
+    if USED_NONCES.has(deps.storage, (signer.pubkey.as_slice(), nonce)) {
+        return Err(ContractError::NonceAlreadyUsed { nonce });
+    }
+    USED_NONCES.save(deps.storage, (signer.pubkey.as_slice(), nonce), &true)?;
+
+    Ok((signer, attestation))
+}
+
+fn find_attestation_signer(deps: Deps, attestation: &[u8; 32], signature: &Binary) -> Result<Signer, ContractError> {
+    for signer in SIGNERS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, signer) = signer?;
+        let verified = match signer.scheme {
+            SignatureScheme::Secp256k1 => deps
+                .api
+                .secp256k1_verify(attestation, signature, &signer.pubkey)
+                .unwrap_or(false),
+            SignatureScheme::Ed25519 => deps
+                .api
+                .ed25519_verify(attestation, signature, &signer.pubkey)
+                .unwrap_or(false),
+        };
+        if verified {
+            return Ok(signer);
+        }
+    }
+
+    Err(ContractError::InvalidSignature {})
+}
+
+pub fn execute_update_signers(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<Signer>,
+    remove: Vec<Binary>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    // Nonces stay recorded for removed signers so re-adding one cannot replay old attestations
+    for pubkey in &remove {
+        SIGNERS.remove(deps.storage, pubkey.as_slice());
+    }
+    for signer in &add {
+        signer.validate()?;
+        SIGNERS.save(deps.storage, signer.pubkey.as_slice(), signer)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "update_signers")
+        .add_attribute("added", add.len().to_string())
+        .add_attribute("removed", remove.len().to_string())
+        .add_event(config_update_event(&info.sender, &["signers"])))
+}
+
+pub fn execute_upgrade_tier(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    proof: Vec<String>,
+    new_points: Uint128,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+
+    let root = CONTRIBUTION_ROOT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ContributionRootNotSet {})?;
+    let leaf = verify_contribution_proof(&root, &info.sender, new_points, &proof)?;
+
+    Ok(upgrade_tier(deps, env, &info.sender, token_id, new_points, leaf)?
+        .add_attribute("method", "upgrade_tier"))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_upgrade_tier_signed(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    points: Uint128,
+    nonce: u64,
+    expires: u64,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+
+    let (signer, attestation) =
+        verify_attestation(deps.branch(), &env, &info.sender, points, nonce, expires, &signature)?;
+
+    Ok(upgrade_tier(deps, env, &info.sender, token_id, points, hex::encode(attestation))?
+        .add_attribute("method", "upgrade_tier_signed")
+        .add_attribute("signer", signer.pubkey.to_base64())
+        .add_attribute("nonce", nonce.to_string()))
+}
+
+// Raises the tier of a contribution token `sender` owns and claimed, once `new_points` has been verified
+fn upgrade_tier(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    token_id: String,
+    new_points: Uint128,
+    claim: String,
+) -> Result<Response, ContractError> {
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let mut token = cw721_contract.tokens.load(deps.storage, &token_id)?;
+    if token.owner != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let current_tier = token
+        .extension
+        .tier
+        .clone()
+        .ok_or(ContractError::NotContributionToken { token_id: token_id.clone() })?;
+
+    // Upgrades extend the allocation the token was minted from, so only its claimant can prove one,
+    // however many other contribution tokens they hold
+    if CONTRIBUTION_CLAIMANTS.may_load(deps.storage, &token_id)?.as_ref() != Some(sender) {
+        return Err(ContractError::NotClaimant { token_id });
+    }
+
+    let new_tier = tier_for_points(new_points);
+    if tier_rank(new_tier) <= tier_rank(&current_tier) {
+        return Err(ContractError::NoTierUpgrade { current: current_tier });
+    }
+
+    CONTRIBUTION_PROOFS.save(deps.storage, sender, &claim)?;
+
+    token.extension.tier = Some(new_tier.to_string());
+    token.extension.contribution_points = Some(new_points);
+    token.token_uri = render_token_uri(deps.storage, &token_id, Some(new_tier))?;
+    cw721_contract.tokens.save(deps.storage, &token_id, &token)?;
+    index_token_tier(deps.storage, &token_id, Some(&current_tier), Some(new_tier))?;
+    let added_weight = tier_weight(Some(new_tier)) - tier_weight(Some(&current_tier));
+    shift_voting_weight(deps.storage, env.block.height, None, Some(sender), added_weight)?;
+
+    Ok(Response::new()
+        .add_event(
+            digm_event("tier_upgrade")
+                .add_attribute("token_id", token_id)
+                .add_attribute("owner", sender)
+                .add_attribute("from_tier", current_tier)
+                .add_attribute("to_tier", new_tier)
+                .add_attribute("contribution_points", new_points),
+        ))
+}
+
+fn tier_for_points(contribution_points: Uint128) -> &'static str {
+    if contribution_points >= Uint128::from(10000u128) {
+        "gold"
+    } else if contribution_points >= Uint128::from(1000u128) {
+        "silver"
+    } else {
+        "bronze"
+    }
+}
+
+fn tier_rank(tier: &str) -> u8 {
+    match tier {
+        "gold" => 3,
+        "silver" => 2,
+        "bronze" => 1,
+        _ => 0,
+    }
+}
+
+// Governance weight a token carries, bronze and curve tokens have no vote
+fn tier_weight(tier: Option<&str>) -> u64 {
+    match tier {
+        Some("gold") => 2,
+        Some("silver") => 1,
+        _ => 0,
+    }
+}
+
+// Vouchers show their origin tier, but votes are only cast with tokens of this collection's own supply
+fn token_weight(storage: &dyn Storage, token_id: &str, extension: &ContractExtension) -> StdResult<u64> {
+    if MINTER_TOKENS.has(storage, token_id) {
+        return Ok(0);
+    }
+    Ok(tier_weight(extension.tier.as_deref()))
+}
+
+// Moves `weight` between holders at `height`, None on either side mints or burns it
+fn shift_voting_weight(
+    storage: &mut dyn Storage,
+    height: u64,
+    from: Option<&Addr>,
+    to: Option<&Addr>,
+    weight: u64,
+) -> StdResult<()> {
+    if weight == 0 || from == to {
+        return Ok(());
+    }
+
+    match from {
+        Some(from) => {
+            VOTING_WEIGHTS.update(storage, from, height, |current| -> StdResult<_> {
+                Ok(current.unwrap_or_default().saturating_sub(weight))
+            })?;
+        }
+        None => {
+            TOTAL_VOTING_WEIGHT.update(storage, height, |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default() + weight)
+            })?;
+        }
+    }
+    match to {
+        Some(to) => {
+            VOTING_WEIGHTS.update(storage, to, height, |current| -> StdResult<_> {
+                Ok(current.unwrap_or_default() + weight)
+            })?;
+        }
+        None => {
+            TOTAL_VOTING_WEIGHT.update(storage, height, |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default().saturating_sub(weight))
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+// Moves a token between tiers in the index and the per-tier counts, None means untiered
+fn index_token_tier(
+    storage: &mut dyn Storage,
+    token_id: &str,
+    old_tier: Option<&str>,
+    new_tier: Option<&str>,
+) -> StdResult<()> {
+    if old_tier == new_tier {
+        return Ok(());
+    }
+
+    if let Some(old_tier) = old_tier {
+        TIER_COUNTS.update(storage, old_tier, |count| -> StdResult<_> {
+            Ok(count.unwrap_or_default().saturating_sub(1))
+        })?;
+    }
+    match new_tier {
+        Some(new_tier) => {
+            TIER_COUNTS.update(storage, new_tier, |count| -> StdResult<_> {
+                Ok(count.unwrap_or_default() + 1)
+            })?;
+            token_tiers().save(storage, token_id, &TokenTier {
+                tier: new_tier.to_string(),
+            })?;
+        }
+        None => token_tiers().remove(storage, token_id)?,
+    }
+
+    Ok(())
+}
+
+// Keeps the count of tokens with their own royalty as one is set, cleared or burned
+fn count_royalty_override(storage: &mut dyn Storage, had: bool, has: bool) -> StdResult<()> {
+    let count = ROYALTY_OVERRIDES.may_load(storage)?.unwrap_or_default();
+    match (had, has) {
+        (false, true) => ROYALTY_OVERRIDES.save(storage, &(count + 1)),
+        (true, false) => ROYALTY_OVERRIDES.save(storage, &count.saturating_sub(1)),
+        _ => Ok(()),
+    }
+}
+
+// Fills the collection's URI template, curve tokens render `{tier}` as "curve"
+fn render_token_uri(storage: &dyn Storage, token_id: &str, tier: Option<&str>) -> StdResult<Option<String>> {
+    let base_uri = METADATA_CONFIG.load(storage)?.base_uri;
+    Ok(base_uri.map(|template| {
+        template
+            .replace("{token_id}", token_id)
+            .replace("{tier}", tier.unwrap_or("curve"))
+    }))
+}
+
+pub fn execute_update_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    album: AlbumMetadata,
+) -> Result<Response, ContractError> {
+    if METADATA_CONFIG.load(deps.storage)?.frozen {
+        return Err(ContractError::MetadataFrozen {});
+    }
+    album.validate(deps.api)?;
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let mut token = cw721_contract.tokens.load(deps.storage, &token_id)?;
+
+    // The artist of the current album may edit it, as may the admin
+    let is_artist = token
+        .extension
+        .album
+        .as_ref()
+        .is_some_and(|current| current.artist == info.sender.as_str());
+    if !is_artist && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    token.extension.album = Some(album);
+    cw721_contract.tokens.save(deps.storage, &token_id, &token)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "update_metadata")
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_freeze_metadata(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    METADATA_CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.frozen = true;
+        Ok(config)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "freeze_metadata")
+        .add_event(config_update_event(&info.sender, &["metadata_frozen"])))
+}
+
+pub fn execute_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    owner: String,
+    token_uri: Option<String>,
+    extension: Option<ContractExtension>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.as_ref())?;
+    // Vouchers keep their origin id, which ics721 sends them back by, while native collections keep
+    // the curve and contribution namespaces to their own mints
+    let voucher_collection = VOUCHER_COLLECTION.may_load(deps.storage)?.unwrap_or_default();
+    if !voucher_collection && (token_id.starts_with("curve-") || token_id.starts_with("contribution-")) {
+        return Err(ContractError::ReservedTokenId { token_id });
+    }
+    let owner = deps.api.addr_validate(&owner)?;
+
+    // Tier, points, royalty and album come back exactly as they left the origin collection
+    let extension = extension.unwrap_or_default();
+    // A royalty is paid out on this chain, so it has to hold up here
+    if let Some(royalty) = &extension.royalty {
+        royalty.validate(deps.api)?;
+    }
+    let tier = extension.tier.clone();
+
+    // The tier is shown as it was minted, but gives no vote and no place in the tier index
+    MINTER_TOKENS.save(deps.storage, &token_id, &true)?;
+    count_royalty_override(deps.storage, false, extension.royalty.is_some())?;
+
+    // cw721 checks the sender is the minter and rejects ids that are already taken
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let response = cw721_contract.execute(deps, env, info, cw721_base::ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: owner.to_string(),
+        token_uri,
+        extension,
+    })?;
+
+    Ok(response
+        .add_attribute("method", "mint")
+        .add_event(
+            digm_event("mint")
+                .add_attribute("token_id", token_id)
+                .add_attribute("owner", owner)
+                .add_attribute("kind", if voucher_collection { "bridge" } else { "minter" })
+                .add_attribute("tier", tier.unwrap_or_default()),
+        ))
+}
+
+pub fn execute_set_class_data(
+    deps: DepsMut,
+    info: MessageInfo,
+    class_data: Binary,
+) -> Result<Response, ContractError> {
+    assert_bridge(deps.as_ref(), &info.sender)?;
+    let class_data: ClassData = from_binary(&class_data).map_err(|err| ContractError::InvalidBridgeData {
+        reason: err.to_string(),
+    })?;
+
+    let mut metadata_config = METADATA_CONFIG.load(deps.storage)?;
+    if metadata_config.frozen {
+        return Err(ContractError::MetadataFrozen {});
+    }
+    // Artist addresses belong to the origin chain, so the album is stored without validating them here
+    match class_data.album {
+        Some(album) => COLLECTION_ALBUM.save(deps.storage, &album)?,
+        None => COLLECTION_ALBUM.remove(deps.storage),
+    }
+    // Royalties are paid out here, so unlike the artist they need a local address
+    match class_data.default_royalty {
+        Some(royalty) => {
+            royalty.validate(deps.api)?;
+            DEFAULT_ROYALTY.save(deps.storage, &royalty)?;
+        }
+        None => DEFAULT_ROYALTY.remove(deps.storage),
+    }
+    metadata_config.base_uri = class_data.base_uri;
+    METADATA_CONFIG.save(deps.storage, &metadata_config)?;
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    cw721_contract.contract_info.save(deps.storage, &cw721::ContractInfoResponse {
+        name: class_data.name,
+        symbol: class_data.symbol,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_class_data")
+        .add_event(config_update_event(&info.sender, &["class_data"])))
+}
+
+// ics721 is the minter of the voucher collections it instantiates, native collections keep their own metadata
+fn assert_bridge(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let minter = Cw721Contract::<ContractExtension, Empty>::default().minter(deps)?.minter;
+    if minter.as_deref() != Some(sender.as_str()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !VOUCHER_COLLECTION.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::NotVoucherCollection {});
+    }
+    Ok(())
+}
+
+pub fn execute_set_contribution_root(
+    deps: DepsMut,
+    info: MessageInfo,
+    root: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    validate_merkle_root(&root)?;
+    CONTRIBUTION_ROOT.save(deps.storage, &root)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_contribution_root")
+        .add_attribute("root", root)
+        .add_event(config_update_event(&info.sender, &["contribution_root"])))
+}
+
+pub fn execute_set_token_royalty(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    royalty: Option<Royalty>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if let Some(royalty) = &royalty {
+        royalty.validate(deps.api)?;
+    }
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let mut token = cw721_contract
+        .tokens
+        .may_load(deps.storage, &token_id)?
+        .ok_or(ContractError::TokenNotFound { token_id: token_id.clone() })?;
+    count_royalty_override(deps.storage, token.extension.royalty.is_some(), royalty.is_some())?;
+    token.extension.royalty = royalty;
+    cw721_contract.tokens.save(deps.storage, &token_id, &token)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_token_royalty")
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_set_soulbound(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    soulbound: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    cw721_contract
+        .tokens
+        .update(deps.storage, &token_id, |token| -> Result<_, ContractError> {
+            let mut token = token.ok_or(ContractError::TokenNotFound { token_id: token_id.clone() })?;
+            token.extension.soulbound = soulbound;
+            // Approvals and rentals granted while transferable would otherwise outlive the lock
+            if soulbound {
+                token.approvals.clear();
+            }
+            Ok(token)
+        })?;
+    if soulbound {
+        token_users().remove(deps.storage, &token_id)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "set_soulbound")
+        .add_attribute("token_id", token_id)
+        .add_attribute("soulbound", soulbound.to_string()))
+}
+
+pub fn execute_set_license_scope(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    album_id: Option<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    cw721_contract
+        .tokens
+        .update(deps.storage, &token_id, |token| -> Result<_, ContractError> {
+            let mut token = token.ok_or(ContractError::TokenNotFound { token_id: token_id.clone() })?;
+            // Clearing the scope has to be recorded, a missing album id falls back to the album
+            token.extension.license_revoked = album_id.is_none();
+            token.extension.album_id = album_id.clone();
+            Ok(token)
+        })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_license_scope")
+        .add_attribute("token_id", token_id)
+        .add_attribute("album_id", album_id.unwrap_or_default()))
+}
+
+pub fn execute_recover_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let mut token = cw721_contract
+        .tokens
+        .may_load(deps.storage, &token_id)?
+        .ok_or(ContractError::TokenNotFound { token_id: token_id.clone() })?;
+    // Transferable tokens can be moved by their owner, the admin never takes them
+    if !token.extension.soulbound {
+        return Err(ContractError::NotSoulbound { token_id });
+    }
+
+    let previous_owner = token.owner;
+    token.owner = recipient.clone();
+    token.approvals.clear();
+    cw721_contract.tokens.save(deps.storage, &token_id, &token)?;
+    token_users().remove(deps.storage, &token_id)?;
+    ESCROW_SENDERS.remove(deps.storage, &token_id);
+    let weight = token_weight(deps.storage, &token_id, &token.extension)?;
+    shift_voting_weight(deps.storage, env.block.height, Some(&previous_owner), Some(&recipient), weight)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "recover_token")
+        .add_event(transfer_event(&token_id, previous_owner.as_str(), recipient.as_str(), "recover"))
+        .add_attribute("token_id", token_id)
+        .add_attribute("from", previous_owner)
+        .add_attribute("to", recipient))
+}
+
+pub fn execute_set_user(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    user: Option<String>,
+    expires: u64,
+) -> Result<Response, ContractError> {
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let token = cw721_contract
+        .tokens
+        .may_load(deps.storage, &token_id)?
+        .ok_or(ContractError::TokenNotFound { token_id: token_id.clone() })?;
+
+    // Same rights as a transfer: the owner, an approved spender or an operator
+    let approved = token
+        .approvals
+        .iter()
+        .any(|approval| approval.spender == info.sender && !approval.expires.is_expired(&env.block));
+    let operator = cw721_contract
+        .operators
+        .may_load(deps.storage, (&token.owner, &info.sender))?
+        .is_some_and(|expires| !expires.is_expired(&env.block));
+    if token.owner != info.sender && !approved && !operator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if token.extension.soulbound {
+        return Err(ContractError::Soulbound { token_id });
+    }
+
+    let mut response = Response::new()
+        .add_attribute("method", "set_user")
+        .add_attribute("token_id", &token_id);
+    match user {
+        Some(user) => {
+            if expires <= env.block.time.seconds() {
+                return Err(ContractError::RentalExpired {});
+            }
+            let user = deps.api.addr_validate(&user)?;
+            response = response
+                .add_attribute("user", &user)
+                .add_attribute("expires", expires.to_string());
+            token_users().save(deps.storage, &token_id, &TokenUser { user, expires })?;
+        }
+        None => token_users().remove(deps.storage, &token_id)?,
+    }
+
+    Ok(response)
+}
+
+fn assert_not_soulbound(deps: Deps, token_id: &str) -> Result<(), ContractError> {
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    // Unknown tokens are left for cw721 to reject
+    if let Some(token) = cw721_contract.tokens.may_load(deps.storage, token_id)? {
+        if token.extension.soulbound {
+            return Err(ContractError::Soulbound {
+                token_id: token_id.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    update: ConfigUpdate,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut response = Response::new().add_attribute("method", "update_config");
+    let mut fields = vec![];
+
+    if let Some(curve) = update.curve {
+        curve.validate()?;
+        // Reserve and unclaimed revenue are held in the current denom and have to stay payable in it
+        let current = CURVE_CONFIG.load(deps.storage)?;
+        if curve.denom != current.denom
+            && !(CURVE_RESERVE.load(deps.storage)?.is_zero()
+                && UNCLAIMED_REVENUE.may_load(deps.storage, &current.denom)?.unwrap_or_default().is_zero()
+                && AUCTION_ESCROW.load(deps.storage)?.is_zero())
+        {
+            return Err(ContractError::InvalidCurveConfig {
+                reason: "cannot change denom while reserve or revenue is outstanding".to_string(),
+            });
+        }
+        CURVE_CONFIG.save(deps.storage, &curve)?;
+        response = response.add_attribute("curve", "updated");
+        fields.push("curve");
+    }
+
+    if let Some(root) = update.contribution_root {
+        validate_merkle_root(&root)?;
+        CONTRIBUTION_ROOT.save(deps.storage, &root)?;
+        response = response.add_attribute("contribution_root", root);
+        fields.push("contribution_root");
+    }
+
+    if let Some(supply) = update.supply {
+        let curve_minted = CURVE_MINTED.load(deps.storage)?;
+        let contribution_minted = CONTRIBUTION_MINTED.load(deps.storage)?;
+        if supply.curve_supply < curve_minted || supply.contribution_supply < contribution_minted {
+            return Err(ContractError::SupplyBelowMinted {});
+        }
+        SUPPLY_CONFIG.save(deps.storage, &supply)?;
+        response = response
+            .add_attribute("curve_supply", supply.curve_supply.to_string())
+            .add_attribute("contribution_supply", supply.contribution_supply.to_string());
+        fields.push("supply");
+    }
+
+    if let Some(royalty) = update.default_royalty {
+        royalty.validate(deps.api)?;
+        DEFAULT_ROYALTY.save(deps.storage, &royalty)?;
+        response = response.add_attribute("default_royalty_bps", royalty.bps.to_string());
+        fields.push("default_royalty");
+    }
+
+    if update.base_uri.is_some() || update.album.is_some() {
+        let mut metadata_config = METADATA_CONFIG.load(deps.storage)?;
+        if metadata_config.frozen {
+            return Err(ContractError::MetadataFrozen {});
+        }
+        if let Some(base_uri) = update.base_uri {
+            response = response.add_attribute("base_uri", &base_uri);
+            fields.push("base_uri");
+            metadata_config.base_uri = Some(base_uri);
+            METADATA_CONFIG.save(deps.storage, &metadata_config)?;
+        }
+        if let Some(album) = update.album {
+            album.validate(deps.api)?;
+            response = response.add_attribute("album_id", &album.album_id);
+            fields.push("album");
+            COLLECTION_ALBUM.save(deps.storage, &album)?;
+        }
+    }
+
+    // Mint counts are kept per phase name, so reusing a name carries them over
+    if let Some(phases) = update.phases {
+        validate_phases(&phases)?;
+        response = response.add_attribute("phases", phases.len().to_string());
+        fields.push("phases");
+        MINT_PHASES.save(deps.storage, &phases)?;
+    }
+
+    if let Some(sale_mode) = update.sale_mode {
+        // Escrowed auction proceeds are only released by settling
+        if let SaleMode::DutchAuction(_) = SALE_MODE.load(deps.storage)? {
+            let auction_id = AUCTION_ID.load(deps.storage)?;
+            let state = AUCTIONS.load(deps.storage, auction_id)?;
+            if state.sold > 0 && state.clearing_price.is_none() {
+                return Err(ContractError::AuctionNotSettled { auction_id });
+            }
+        }
+        // Auctions disable sell-backs, which would strand whatever the reserve holds
+        if matches!(sale_mode, SaleMode::DutchAuction(_)) && !CURVE_RESERVE.load(deps.storage)?.is_zero() {
+            return Err(ContractError::InvalidAuction {
+                reason: "cannot start an auction while the curve reserve is funded".to_string(),
+            });
+        }
+        save_sale_mode(deps.storage, &sale_mode)?;
+        response = response.add_attribute("sale_mode", match sale_mode {
+            SaleMode::BondingCurve {} => "bonding_curve",
+            SaleMode::DutchAuction(_) => "dutch_auction",
+        });
+        fields.push("sale_mode");
+    }
+
+    if let Some(soulbound) = update.soulbound_contributions {
+        SOULBOUND_CONTRIBUTIONS.save(deps.storage, &soulbound)?;
+        response = response.add_attribute("soulbound_contributions", soulbound.to_string());
+        fields.push("soulbound_contributions");
+    }
+
+    if let Some(governance) = update.governance {
+        governance.validate()?;
+        GOVERNANCE_CONFIG.save(deps.storage, &governance)?;
+        fields.push("governance");
+    }
+
+    // Rates accepted under the previous oracle are no reference for the new one
+    if let Some(pricing) = update.pricing {
+        pricing.validate(deps.api)?;
+        PRICING.save(deps.storage, &pricing)?;
+        let denoms = LAST_RATES
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for denom in denoms {
+            LAST_RATES.remove(deps.storage, &denom);
+        }
+        response = response.add_attribute("pricing_denoms", pricing.denoms.join(","));
+        fields.push("pricing");
+    }
+
+    // Revenue already credited stays claimable by the previous payees
+    if let Some(payees) = update.payees {
+        validate_payees(deps.api, &payees)?;
+        PAYEES.save(deps.storage, &payees)?;
+        response = response.add_attribute("payees", payees.len().to_string());
+        fields.push("payees");
+    }
+
+    Ok(response.add_event(config_update_event(&info.sender, &fields)))
+}
+
+pub fn execute_withdraw(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let amount = take_claimable(deps.storage, &info.sender)?;
+    if amount.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "withdraw")
+        .add_attribute("payee", &info.sender)
+        .add_attribute("amount", format_coins(&amount))
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount,
+        }))
+}
+
+pub fn execute_distribute_proceeds(deps: DepsMut) -> Result<Response, ContractError> {
+    let mut payees = CLAIMABLE
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| key.map(|(payee, _)| payee))
+        .collect::<StdResult<Vec<_>>>()?;
+    payees.dedup();
+
+    let mut response = Response::new().add_attribute("method", "distribute_proceeds");
+    let mut paid = 0;
+    for payee in payees {
+        let amount = take_claimable(deps.storage, &payee)?;
+        if amount.is_empty() {
+            continue;
+        }
+        paid += 1;
+        response = response.add_message(BankMsg::Send {
+            to_address: payee.to_string(),
+            amount,
+        });
+    }
+
+    if paid == 0 {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(response.add_attribute("payees", paid.to_string()))
+}
+
+// Removes everything owed to `payee`, one coin per denom sorted by denom
+fn take_claimable(storage: &mut dyn Storage, payee: &Addr) -> StdResult<Vec<Coin>> {
+    let claimable = CLAIMABLE
+        .prefix(payee)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut amount = vec![];
+    for (denom, claimed) in claimable {
+        CLAIMABLE.remove(storage, (payee, &denom));
+        UNCLAIMED_REVENUE.update(storage, &denom, |unclaimed| -> StdResult<_> {
+            Ok(unclaimed.unwrap_or_default().checked_sub(claimed)?)
+        })?;
+        if !claimed.is_zero() {
+            amount.push(coin(claimed.u128(), denom));
+        }
+    }
+    Ok(amount)
+}
+
+fn format_coins(amount: &[Coin]) -> String {
+    amount.iter().map(Coin::to_string).collect::<Vec<_>>().join(",")
+}
+
+// Splits `revenue` in `denom` across payees by weight after the platform fee, rounding dust goes to the first payee
+fn credit_revenue(storage: &mut dyn Storage, revenue: Uint128, denom: &str) -> StdResult<()> {
+    if revenue.is_zero() {
+        return Ok(());
+    }
+    UNCLAIMED_REVENUE.update(storage, denom, |unclaimed| -> StdResult<_> {
+        Ok(unclaimed.unwrap_or_default().checked_add(revenue)?)
+    })?;
+
+    let mut revenue = revenue;
+    if let Some(platform_fee) = PLATFORM_FEE.may_load(storage)? {
+        let fee = revenue.multiply_ratio(platform_fee.bps, BPS_DENOMINATOR);
+        let recipient = Addr::unchecked(&platform_fee.recipient);
+        CLAIMABLE.update(storage, (&recipient, denom), |claimable| -> StdResult<_> {
+            Ok(claimable.unwrap_or_default().checked_add(fee)?)
+        })?;
+        revenue -= fee;
+    }
+
+    let payees = PAYEES.load(storage)?;
+    // Summed wider than the weights so no payee list can overflow it
+    let total_weight: u128 = payees.iter().map(|payee| u128::from(payee.weight)).sum();
+
+    let mut distributed = Uint128::zero();
+    let mut shares = payees
+        .iter()
+        .map(|payee| {
+            let share = revenue.multiply_ratio(payee.weight, total_weight);
+            distributed += share;
+            (Addr::unchecked(&payee.address), share)
+        })
+        .collect::<Vec<_>>();
+    shares[0].1 += revenue - distributed;
+
+    for (payee, share) in shares {
+        CLAIMABLE.update(storage, (&payee, denom), |claimable| -> StdResult<_> {
+            Ok(claimable.unwrap_or_default().checked_add(share)?)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn validate_payees(api: &dyn Api, payees: &[Payee]) -> Result<(), ContractError> {
+    if payees.is_empty() || payees.len() > MAX_PAYEES {
+        return Err(ContractError::InvalidPayees {
+            reason: format!("between 1 and {} payees required", MAX_PAYEES),
+        });
+    }
+
+    let mut seen = Vec::with_capacity(payees.len());
+    let mut total_weight = 0u64;
+    for payee in payees {
+        let address = api.addr_validate(&payee.address)?;
+        if payee.weight == 0 {
+            return Err(ContractError::InvalidPayees {
+                reason: format!("{} has zero weight", address),
+            });
+        }
+        if seen.contains(&address) {
+            return Err(ContractError::InvalidPayees {
+                reason: format!("{} is listed twice", address),
+            });
+        }
+        total_weight = total_weight.checked_add(payee.weight).ok_or_else(|| ContractError::InvalidPayees {
+            reason: "total weight overflows".to_string(),
+        })?;
+        seen.push(address);
+    }
+    if total_weight == 0 {
+        return Err(ContractError::InvalidPayees {
+            reason: "total weight is zero".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("method", if paused { "pause" } else { "unpause" })
+        .add_event(config_update_event(&info.sender, &["paused"])))
+}
+
+pub fn execute_propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    description: String,
+    action: ProposalAction,
+) -> Result<Response, ContractError> {
+    // Weights are read at the start of the block, so tokens received in it cannot open a proposal
+    let snapshot_height = env.block.height;
+    let weight = VOTING_WEIGHTS
+        .may_load_at_height(deps.storage, &info.sender, snapshot_height)?
+        .unwrap_or_default();
+    if weight == 0 {
+        return Err(ContractError::NoVotingPower {});
+    }
+    validate_proposal_action(deps.as_ref(), &action)?;
+
+    let config = GOVERNANCE_CONFIG.load(deps.storage)?;
+    let total_weight = TOTAL_VOTING_WEIGHT
+        .may_load_at_height(deps.storage, snapshot_height)?
+        .unwrap_or_default();
+    let id = PROPOSAL_COUNT.load(deps.storage)? + 1;
+    PROPOSAL_COUNT.save(deps.storage, &id)?;
+    PROPOSALS.save(deps.storage, id, &Proposal {
+        id,
+        proposer: info.sender.clone(),
+        title,
+        description,
+        action,
+        snapshot_height,
+        expires: env.block.time.plus_seconds(config.voting_period),
+        quorum_bps: config.quorum_bps,
+        threshold_bps: config.threshold_bps,
+        total_weight,
+        yes: 0,
+        no: 0,
+        abstain: 0,
+        status: ProposalStatus::Open {},
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "propose")
+        .add_attribute("proposal_id", id.to_string())
+        .add_attribute("proposer", info.sender))
+}
+
+pub fn execute_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: VoteOption,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_open_proposal(deps.storage, proposal_id)?;
+    if env.block.time >= proposal.expires {
+        return Err(ContractError::VotingEnded { proposal_id });
+    }
+    if VOTES.has(deps.storage, (proposal_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted { proposal_id });
+    }
+
+    let weight = VOTING_WEIGHTS
+        .may_load_at_height(deps.storage, &info.sender, proposal.snapshot_height)?
+        .unwrap_or_default();
+    if weight == 0 {
+        return Err(ContractError::NoVotingPower {});
+    }
+    match vote {
+        VoteOption::Yes {} => proposal.yes += weight,
+        VoteOption::No {} => proposal.no += weight,
+        VoteOption::Abstain {} => proposal.abstain += weight,
+    }
+    VOTES.save(deps.storage, (proposal_id, &info.sender), &vote)?;
+
+    let mut response = Response::new()
+        .add_attribute("method", "vote")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_attribute("voter", info.sender)
+        .add_attribute("weight", weight.to_string());
+    if proposal.decided() {
+        response = execute_proposal_action(deps, &env, &mut proposal, response)?;
+    } else {
+        PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    }
+
+    Ok(response)
+}
+
+pub fn execute_close_proposal(deps: DepsMut, env: Env, proposal_id: u64) -> Result<Response, ContractError> {
+    let mut proposal = load_open_proposal(deps.storage, proposal_id)?;
+    if env.block.time < proposal.expires {
+        return Err(ContractError::VotingNotEnded { proposal_id });
+    }
+
+    let response = Response::new()
+        .add_attribute("method", "close_proposal")
+        .add_attribute("proposal_id", proposal_id.to_string());
+    if proposal.passed() {
+        return execute_proposal_action(deps, &env, &mut proposal, response);
+    }
+
+    proposal.status = ProposalStatus::Rejected {};
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    Ok(response.add_attribute("status", "rejected"))
+}
+
+fn load_open_proposal(storage: &dyn Storage, proposal_id: u64) -> Result<Proposal, ContractError> {
+    let proposal = PROPOSALS
+        .may_load(storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { proposal_id })?;
+    if !matches!(proposal.status, ProposalStatus::Open {}) {
+        return Err(ContractError::ProposalClosed { proposal_id });
+    }
+    Ok(proposal)
+}
+
+// Applies a passed proposal's change, with the contract itself as the sender of the config update
+fn execute_proposal_action(
+    deps: DepsMut,
+    env: &Env,
+    proposal: &mut Proposal,
+    response: Response,
+) -> Result<Response, ContractError> {
+    // A change the admin has since made impossible closes the proposal instead of blocking it
+    if let Err(err) = validate_proposal_action(deps.as_ref(), &proposal.action) {
+        proposal.status = ProposalStatus::Rejected {};
+        PROPOSALS.save(deps.storage, proposal.id, proposal)?;
+        return Ok(response
+            .add_attribute("status", "failed")
+            .add_attribute("reason", err.to_string()));
+    }
+
+    let field = match &proposal.action {
+        ProposalAction::SetCurveRate { rate } => {
+            CURVE_CONFIG.update(deps.storage, |mut curve| -> StdResult<_> {
+                curve.rate = *rate;
+                Ok(curve)
+            })?;
+            "curve"
+        }
+        ProposalAction::SetRoyaltyBps { bps } => {
+            DEFAULT_ROYALTY.update(deps.storage, |mut royalty| -> StdResult<_> {
+                royalty.bps = *bps;
+                Ok(royalty)
+            })?;
+            "default_royalty"
+        }
+        ProposalAction::SetPayees { payees } => {
+            PAYEES.save(deps.storage, payees)?;
+            "payees"
+        }
+        ProposalAction::SetContributionRoot { root } => {
+            CONTRIBUTION_ROOT.save(deps.storage, root)?;
+            "contribution_root"
+        }
+    };
+    proposal.status = ProposalStatus::Executed {};
+    PROPOSALS.save(deps.storage, proposal.id, proposal)?;
+
+    let event = config_update_event(&env.contract.address, &[field])
+        .add_attribute("proposal_id", proposal.id.to_string());
+    Ok(response.add_attribute("status", "executed").add_event(event))
+}
+
+// Checked when proposing and again before executing, since the admin may change the config in between
+fn validate_proposal_action(deps: Deps, action: &ProposalAction) -> Result<(), ContractError> {
+    match action {
+        ProposalAction::SetCurveRate { rate } => {
+            let mut curve = CURVE_CONFIG.load(deps.storage)?;
+            if matches!(curve.curve_type, CurveType::Exponential { .. } | CurveType::PiecewiseStep { .. }) {
+                return Err(ContractError::InvalidProposal {
+                    reason: "the collection curve has no rate".to_string(),
+                });
+            }
+            curve.rate = *rate;
+            curve.validate()?;
+        }
+        ProposalAction::SetRoyaltyBps { bps } => {
+            let mut royalty = DEFAULT_ROYALTY.may_load(deps.storage)?.ok_or(ContractError::InvalidProposal {
+                reason: "the collection has no default royalty".to_string(),
+            })?;
+            royalty.bps = *bps;
+            royalty.validate(deps.api)?;
+        }
+        ProposalAction::SetPayees { payees } => validate_payees(deps.api, payees)?,
+        ProposalAction::SetContributionRoot { root } => validate_merkle_root(root)?,
+    }
+    Ok(())
+}
+
+fn digm_event(kind: &str) -> Event {
+    Event::new(format!("digm_{}", kind)).add_attribute("schema_version", EVENT_SCHEMA_VERSION)
+}
+
+fn curve_mint_event(token_id: &str, owner: &Addr, price: Uint128, denom: &str) -> Event {
+    digm_event("mint")
+        .add_attribute("token_id", token_id)
+        .add_attribute("owner", owner)
+        .add_attribute("kind", "curve")
+        .add_attribute("price", price)
+        .add_attribute("denom", denom)
+}
+
+fn contribution_mint_event(token_id: &str, owner: &Addr, tier: &str, contribution_points: Uint128) -> Event {
+    digm_event("mint")
+        .add_attribute("token_id", token_id)
+        .add_attribute("owner", owner)
+        .add_attribute("kind", "contribution")
+        .add_attribute("tier", tier)
+        .add_attribute("contribution_points", contribution_points)
+}
+
+fn transfer_event(token_id: &str, from: &str, to: &str, kind: &str) -> Event {
+    digm_event("transfer")
+        .add_attribute("token_id", token_id)
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("kind", kind)
+}
+
+fn burn_event(token_id: &str, owner: &Addr, kind: &str) -> Event {
+    digm_event("burn")
+        .add_attribute("token_id", token_id)
+        .add_attribute("owner", owner)
+        .add_attribute("kind", kind)
+}
+
+fn config_update_event(sender: &Addr, fields: &[&str]) -> Event {
+    digm_event("config_update")
+        .add_attribute("sender", sender)
+        .add_attribute("fields", fields.join(","))
+}
+
+fn assert_not_paused(deps: Deps) -> Result<(), ContractError> {
+    if PAUSED.load(deps.storage)? {
+        return Err(ContractError::Paused {});
+    }
+    Ok(())
+}
+
+// Voucher collections have no curve or contribution supply of their own
+fn assert_native_collection(deps: Deps) -> Result<(), ContractError> {
+    if VOUCHER_COLLECTION.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::VoucherCollection {});
+    }
+    Ok(())
+}
+
+fn validate_merkle_root(root: &str) -> Result<(), ContractError> {
+    let mut root_buf = [0u8; 32];
+    hex::decode_to_slice(root, &mut root_buf)?;
+    Ok(())
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Checks `proof` against the stored root and returns the hex-encoded leaf.
+///
+/// Leaves are `sha256("{address}{contribution_points}")` and each level hashes the
+/// sorted pair of nodes, matching the cw20-merkle-airdrop tree layout so existing
+/// tooling can generate the root and proofs.
+fn verify_contribution_proof(
+    root: &str,
+    address: &Addr,
+    contribution_points: Uint128,
+    proof: &[String],
+) -> Result<String, ContractError> {
+    let leaf = sha256(format!("{}{}", address, contribution_points).as_bytes());
+    verify_merkle_proof(root, leaf, proof)?;
+    Ok(hex::encode(leaf))
+}
+
+fn verify_merkle_proof(root: &str, leaf: [u8; 32], proof: &[String]) -> Result<(), ContractError> {
+    let computed = proof.iter().try_fold(leaf, |hash, node| {
+        let mut node_buf = [0u8; 32];
+        hex::decode_to_slice(node, &mut node_buf)?;
+        let mut pair = [hash, node_buf];
+        pair.sort_unstable();
+        Ok::<_, ContractError>(sha256(&pair.concat()))
+    })?;
+
+    let mut root_buf = [0u8; 32];
+    hex::decode_to_slice(root, &mut root_buf)?;
+    if computed != root_buf {
+        return Err(ContractError::InvalidProof {});
+    }
+
+    Ok(())
+}
+
+impl CurveConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.denom.is_empty() {
+            return Err(ContractError::InvalidCurveConfig {
+                reason: "curve denom cannot be empty".to_string(),
+            });
+        }
+        if self.sell_spread_bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidCurveConfig {
+                reason: "sell spread cannot exceed 10000 bps".to_string(),
+            });
+        }
+
+        match &self.curve_type {
+            CurveType::Exponential { growth } if growth.is_zero() => {
+                Err(ContractError::InvalidCurveConfig {
+                    reason: "exponential growth must be positive".to_string(),
+                })
+            }
+            CurveType::Polynomial { exponent } if *exponent == 0 => {
+                Err(ContractError::InvalidCurveConfig {
+                    reason: "polynomial exponent must be at least 1".to_string(),
+                })
+            }
+            CurveType::PiecewiseStep { steps } => {
+                if steps.is_empty() {
+                    return Err(ContractError::InvalidCurveConfig {
+                        reason: "piecewise curve needs at least one step".to_string(),
+                    });
+                }
+                if steps.windows(2).any(|w| w[0].from_supply >= w[1].from_supply) {
+                    return Err(ContractError::InvalidCurveConfig {
+                        reason: "piecewise steps must have increasing from_supply".to_string(),
+                    });
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Price of the next token when `current_supply` tokens have been minted
+    pub fn price_at(&self, current_supply: u32) -> StdResult<Uint128> {
+        let supply = Uint128::from(current_supply);
+        let price = match &self.curve_type {
+            CurveType::Linear {} => self.start_price.checked_add(self.rate.checked_mul(supply)?)?,
+            CurveType::Exponential { growth } => {
+                let factor = (Decimal::one() + *growth).checked_pow(current_supply)?;
+                self.start_price
+                    .checked_mul_floor(factor)
+                    .map_err(|err| StdError::generic_err(err.to_string()))?
+            }
+            CurveType::Polynomial { exponent } => self
+                .start_price
+                .checked_add(self.rate.checked_mul(supply.checked_pow(*exponent)?)?)?,
+            CurveType::PiecewiseStep { steps } => steps
+                .iter()
+                .take_while(|step| step.from_supply <= current_supply)
+                .last()
+                .map(|step| step.price)
+                .unwrap_or(self.start_price),
+        };
+        Ok(price)
+    }
+
+    // Per-token prices for minting `count` tokens on top of `current_supply`
+    pub fn simulate_mint(&self, current_supply: u32, count: u32) -> StdResult<SimulateMintResponse> {
+        let prices = (current_supply..current_supply + count)
+            .map(|supply| self.price_at(supply))
+            .collect::<StdResult<Vec<_>>>()?;
+        let total_price = prices
+            .iter()
+            .try_fold(Uint128::zero(), |total, price| total.checked_add(*price))?;
+
+        Ok(SimulateMintResponse { total_price, prices })
+    }
+
+    // What the reserve pays back for a token priced at `price`
+    pub fn sell_price(&self, price: Uint128) -> Uint128 {
+        price.multiply_ratio(BPS_DENOMINATOR - self.sell_spread_bps, BPS_DENOMINATOR)
+    }
+}
+
+impl DutchAuctionConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.floor_price > self.start_price {
+            return Err(ContractError::InvalidAuction {
+                reason: "floor price cannot exceed the start price".to_string(),
+            });
+        }
+        if self.step_seconds == 0 || self.step_decrease.is_zero() {
+            return Err(ContractError::InvalidAuction {
+                reason: "step duration and decrease must be positive".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    // Number of price drops between the start and the floor
+    pub fn steps_to_floor(&self) -> u64 {
+        let range = self.start_price - self.floor_price;
+        let steps = range.u128().div_ceil(self.step_decrease.u128());
+        u64::try_from(steps).unwrap_or(u64::MAX)
+    }
+
+    pub fn floor_reached_at(&self) -> Timestamp {
+        let seconds = self.steps_to_floor().saturating_mul(self.step_seconds);
+        self.start_time.plus_seconds(seconds)
+    }
+
+    pub fn price_at(&self, now: Timestamp) -> Uint128 {
+        let elapsed = now.seconds().saturating_sub(self.start_time.seconds());
+        self.price_after_steps(elapsed / self.step_seconds)
+    }
+
+    fn price_after_steps(&self, steps: u64) -> Uint128 {
+        self.step_decrease
+            .checked_mul(Uint128::from(steps))
+            .map(|decrease| self.start_price.saturating_sub(decrease))
+            .unwrap_or_default()
+            .max(self.floor_price)
+    }
+
+    pub fn schedule(&self) -> Vec<AuctionStep> {
+        (0..=self.steps_to_floor().min(MAX_AUCTION_SCHEDULE - 1))
+            .map(|step| AuctionStep {
+                time: self.start_time.plus_seconds(step * self.step_seconds),
+                price: self.price_after_steps(step),
+            })
+            .collect()
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetCurvePrice {} => to_binary(&query_curve_price(deps, env)?),
+        QueryMsg::GetCurveConfig {} => to_binary(&CURVE_CONFIG.load(deps.storage)?),
+        QueryMsg::GetReserve {} => to_binary(&query_reserve(deps)?),
+        QueryMsg::GetBacking { token_id } => to_binary(&query_backing(deps, token_id)?),
+        QueryMsg::SimulateMint { count } => to_binary(&query_simulate_mint(deps, env, count)?),
+        QueryMsg::QuotePayment { denom, count } => to_binary(&query_quote_payment(deps, env, denom, count)?),
+        QueryMsg::GetMintPhases {} => to_binary(&query_mint_phases(deps, env)?),
+        QueryMsg::GetPhaseMints { phase, address } => {
+            to_binary(&query_phase_mints(deps, phase, address)?)
+        }
+        QueryMsg::GetSaleMode {} => to_binary(&SALE_MODE.load(deps.storage)?),
+        QueryMsg::GetAuction {} => to_binary(&query_auction(deps, env)?),
+        QueryMsg::GetRebate { auction_id, address } => {
+            to_binary(&query_rebate(deps, auction_id, address)?)
+        }
+        QueryMsg::GetCurveSupply {} => to_binary(&query_curve_supply(deps)?),
+        QueryMsg::GetContributionSupply {} => to_binary(&query_contribution_supply(deps)?),
+        QueryMsg::GetContributionRoot {} => to_binary(&query_contribution_root(deps)?),
+        QueryMsg::GetSigners {} => to_binary(&query_signers(deps)?),
+        QueryMsg::GetAdmin {} => to_binary(&ADMIN.query_admin(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::GetPayees {} => to_binary(&PayeesResponse {
+            payees: PAYEES.load(deps.storage)?,
+        }),
+        QueryMsg::GetMetadataConfig {} => to_binary(&query_metadata_config(deps)?),
+        QueryMsg::GetClaimable { address } => to_binary(&query_claimable(deps, address)?),
+        QueryMsg::TokensByTier { tier, start_after, limit } => {
+            to_binary(&query_tokens_by_tier(deps, tier, start_after, limit)?)
+        }
+        QueryMsg::TierCounts {} => to_binary(&query_tier_counts(deps)?),
+        QueryMsg::OwnerTierSummary { owner } => to_binary(&query_owner_tier_summary(deps, owner)?),
+        QueryMsg::HasEntitlement { owner, album_id } => {
+            to_binary(&query_has_entitlement(deps, env, owner, album_id)?)
+        }
+        QueryMsg::EntitlementsOf { owner } => to_binary(&query_entitlements_of(deps, env, owner)?),
+        QueryMsg::UserOf { token_id } => to_binary(&query_user_of(deps, env, token_id)?),
+        QueryMsg::ClassData {} => to_binary(&query_class_data(deps)?),
+        QueryMsg::TokenData { token_id } => to_binary(&query_token_data(deps, token_id)?),
+        QueryMsg::GetGovernanceConfig {} => to_binary(&GOVERNANCE_CONFIG.load(deps.storage)?),
+        QueryMsg::GetProposal { proposal_id } => to_binary(&PROPOSALS.load(deps.storage, proposal_id)?),
+        QueryMsg::ListProposals { start_after, limit } => {
+            to_binary(&query_list_proposals(deps, start_after, limit)?)
+        }
+        QueryMsg::GetVote { proposal_id, voter } => to_binary(&query_vote(deps, proposal_id, voter)?),
+        QueryMsg::VotingPower { address, height } => {
+            to_binary(&query_voting_power(deps, env, address, height)?)
+        }
+        QueryMsg::Extension { msg } => match msg {
+            Cw2981QueryMsg::RoyaltyInfo { token_id, sale_price } => {
+                to_binary(&query_royalty_info(deps, token_id, sale_price)?)
+            }
+            Cw2981QueryMsg::CheckRoyalties {} => to_binary(&query_check_royalties(deps)?),
+        },
+        // Delegate other queries to cw721 base
+        _ => {
+            let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+            let msg = msg
+                .try_into()
+                .map_err(|err: ContractError| StdError::generic_err(err.to_string()))?;
+            cw721_contract.query(deps, env, msg)
+        }
+    }
+}
+
+fn query_curve_price(deps: Deps, env: Env) -> StdResult<CurveInfoResponse> {
+    let curve_minted = CURVE_MINTED.load(deps.storage)?;
+    let contribution_minted = CONTRIBUTION_MINTED.load(deps.storage)?;
+    let current_price = match SALE_MODE.load(deps.storage)? {
+        SaleMode::BondingCurve {} => CURVE_CONFIG.load(deps.storage)?.price_at(curve_minted)?,
+        SaleMode::DutchAuction(auction) => auction.price_at(env.block.time),
+    };
+    let supply = SUPPLY_CONFIG.load(deps.storage)?;
+    
+    Ok(CurveInfoResponse {
+        current_price,
+        curve_minted,
+        contribution_minted,
+        total_minted: curve_minted.saturating_add(contribution_minted),
+        // Caps are set independently, so their sum may not fit
+        max_supply: supply.curve_supply.saturating_add(supply.contribution_supply),
+    })
+}
+
+fn query_tokens_by_tier(
+    deps: Deps,
+    tier: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensByTierResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let tokens = token_tiers()
+        .idx
+        .tier
+        .prefix(tier.clone())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensByTierResponse { tier, tokens })
+}
+
+fn query_tier_counts(deps: Deps) -> StdResult<TierCountsResponse> {
+    let tiers = TIER_COUNTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(tier, count)| TierCount { tier, count }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TierCountsResponse { tiers })
+}
+
+// Walks only the owner's tokens through the cw721 owner index
+fn query_owner_tier_summary(deps: Deps, owner: String) -> StdResult<OwnerTierSummaryResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+
+    let mut tiers: Vec<TierCount> = vec![];
+    for item in cw721_contract
+        .tokens
+        .idx
+        .owner
+        .prefix(owner_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (_, token) = item?;
+        let Some(tier) = token.extension.tier else {
+            continue;
+        };
+        match tiers.iter_mut().find(|entry| entry.tier == tier) {
+            Some(entry) => entry.count += 1,
+            None => tiers.push(TierCount { tier, count: 1 }),
+        }
+    }
+    tiers.sort_by_key(|entry| std::cmp::Reverse(tier_rank(&entry.tier)));
+    let highest_tier = tiers.first().map(|entry| entry.tier.clone());
+
+    Ok(OwnerTierSummaryResponse {
+        owner,
+        tiers,
+        highest_tier,
+    })
+}
+
+fn query_has_entitlement(deps: Deps, env: Env, owner: String, album_id: String) -> StdResult<HasEntitlementResponse> {
+    let token_id = entitlements(deps, &env, &owner)?
+        .into_iter()
+        .find(|entitlement| entitlement.album_id == album_id)
+        .map(|entitlement| entitlement.token_id);
+
+    Ok(HasEntitlementResponse {
+        entitled: token_id.is_some(),
+        token_id,
+    })
+}
+
+fn query_entitlements_of(deps: Deps, env: Env, owner: String) -> StdResult<EntitlementsResponse> {
+    Ok(EntitlementsResponse {
+        entitlements: entitlements(deps, &env, &owner)?,
+        owner,
+    })
+}
+
+// Album licenses held by `owner`, one entry per licensing token.
+// An active renter holds the license instead of the token owner until the rental expires
+fn entitlements(deps: Deps, env: &Env, owner: &str) -> StdResult<Vec<Entitlement>> {
+    let owner_addr = deps.api.addr_validate(owner)?;
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let now = env.block.time;
+
+    let mut entitlements = vec![];
+    for item in cw721_contract
+        .tokens
+        .idx
+        .owner
+        .prefix(owner_addr.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (token_id, token) = item?;
+        let lent_out = token_users()
+            .may_load(deps.storage, &token_id)?
+            .is_some_and(|rental| rental.is_active(now) && rental.user != owner_addr);
+        if let (Some(album_id), false) = (token.extension.license_album_id(), lent_out) {
+            entitlements.push(Entitlement {
+                album_id: album_id.to_string(),
+                token_id,
+                expires: None,
+            });
+        }
+    }
+
+    for item in token_users()
+        .idx
+        .user
+        .prefix(owner_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (token_id, rental) = item?;
+        if !rental.is_active(now) {
+            continue;
+        }
+        let token = cw721_contract.tokens.load(deps.storage, &token_id)?;
+        if let Some(album_id) = token.extension.license_album_id() {
+            entitlements.push(Entitlement {
+                album_id: album_id.to_string(),
+                token_id,
+                expires: Some(rental.expires),
+            });
+        }
+    }
+
+    Ok(entitlements)
+}
+
+fn query_user_of(deps: Deps, env: Env, token_id: String) -> StdResult<UserOfResponse> {
+    let rental = token_users()
+        .may_load(deps.storage, &token_id)?
+        .filter(|rental| rental.is_active(env.block.time));
+
+    Ok(UserOfResponse {
+        user: rental.as_ref().map(|rental| rental.user.to_string()),
+        expires: rental.map(|rental| rental.expires),
+    })
+}
 
-    // Initialize curve state
-    CURVE_MINTED.save(deps.storage, "total", &0)?;
-    CONTRIBUTION_MINTED.save(deps.storage, "total", &0)?;
+fn query_list_proposals(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ProposalsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let proposals = PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, proposal)| proposal))
+        .collect::<StdResult<Vec<_>>>()?;
 
-    // Initialize cw721 base contract
-    let cw721_msg = Cw721InstantiateMsg {
-        name: msg.name,
-        symbol: msg.symbol,
-        minter: msg.minter,
+    Ok(ProposalsResponse { proposals })
+}
+
+fn query_vote(deps: Deps, proposal_id: u64, voter: String) -> StdResult<VoteResponse> {
+    let voter = deps.api.addr_validate(&voter)?;
+    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    let vote = VOTES.may_load(deps.storage, (proposal_id, &voter))?;
+    let weight = VOTING_WEIGHTS
+        .may_load_at_height(deps.storage, &voter, proposal.snapshot_height)?
+        .unwrap_or_default();
+
+    Ok(VoteResponse { vote, weight })
+}
+
+fn query_voting_power(deps: Deps, env: Env, address: String, height: Option<u64>) -> StdResult<VotingPowerResponse> {
+    let voter = deps.api.addr_validate(&address)?;
+    let (weight, total_weight) = match height {
+        Some(height) if height <= env.block.height => (
+            VOTING_WEIGHTS.may_load_at_height(deps.storage, &voter, height)?,
+            TOTAL_VOTING_WEIGHT.may_load_at_height(deps.storage, height)?,
+        ),
+        Some(height) => {
+            return Err(StdError::generic_err(format!("Height {} is in the future", height)));
+        }
+        None => (
+            VOTING_WEIGHTS.may_load(deps.storage, &voter)?,
+            TOTAL_VOTING_WEIGHT.may_load(deps.storage)?,
+        ),
     };
 
+    Ok(VotingPowerResponse {
+        address,
+        weight: weight.unwrap_or_default(),
+        total_weight: total_weight.unwrap_or_default(),
+    })
+}
+
+fn query_class_data(deps: Deps) -> StdResult<ClassData> {
     let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
-    cw721_contract.instantiate(deps, _env, info, cw721_msg)?;
+    let contract_info = cw721_contract.contract_info.load(deps.storage)?;
 
-    Ok(Response::new()
-        .add_attribute("method", "instantiate")
-        .add_attribute("curve_start_price", msg.curve_start_price)
-        .add_attribute("curve_rate", msg.curve_rate))
+    Ok(ClassData {
+        name: contract_info.name,
+        symbol: contract_info.symbol,
+        album: COLLECTION_ALBUM.may_load(deps.storage)?,
+        default_royalty: DEFAULT_ROYALTY.may_load(deps.storage)?,
+        base_uri: METADATA_CONFIG.load(deps.storage)?.base_uri,
+    })
 }
 
-#[entry_point]
-pub fn execute(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::MintCurve {} => execute_mint_curve(deps, env, info),
-        ExecuteMsg::MintContribution { proof, contribution_points } => {
-            execute_mint_contribution(deps, env, info, proof, contribution_points)
-        }
-        // Delegate other messages to cw721 base
-        _ => {
-            let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
-            cw721_contract.execute(deps, env, info, msg.into())?;
-            Ok(Response::new())
-        }
-    }
+fn query_token_data(deps: Deps, token_id: String) -> StdResult<TokenDataResponse> {
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let token = cw721_contract.tokens.load(deps.storage, &token_id)?;
+
+    Ok(TokenDataResponse {
+        token_uri: token.token_uri,
+        token_data: to_binary(&token.extension)?,
+    })
 }
 
-pub fn execute_mint_curve(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-) -> Result<Response, ContractError> {
-    let current_curve_minted = CURVE_MINTED.load(deps.storage, "total")?;
-    
-    if current_curve_minted >= CURVE_SUPPLY {
-        return Err(ContractError::CurveSupplyExhausted {});
-    }
+fn query_metadata_config(deps: Deps) -> StdResult<MetadataConfigResponse> {
+    let MetadataConfig { base_uri, frozen } = METADATA_CONFIG.load(deps.storage)?;
 
-    // Calculate price based on bonding curve
-    let price = calculate_curve_price(current_curve_minted)?;
-    
-    // Verify payment
-    if info.funds.len() != 1 || info.funds[0].amount < price {
-        return Err(ContractError::InsufficientPayment { required: price });
-    }
+    Ok(MetadataConfigResponse {
+        base_uri,
+        frozen,
+        album: COLLECTION_ALBUM.may_load(deps.storage)?,
+    })
+}
 
-    // Mint NFT
-    let token_id = format!("curve-{}", current_curve_minted + 1);
-    let token_uri = format!("ipfs://digm-curve-{}", current_curve_minted + 1);
-    
-    let token_info = TokenInfo {
-        owner: deps.api.addr_validate(&info.sender.to_string())?,
-        approvals: vec![],
-        token_uri: Some(token_uri),
-        extension: ContractExtension::default(),
-    };
+fn query_claimable(deps: Deps, address: String) -> StdResult<ClaimableResponse> {
+    let payee = deps.api.addr_validate(&address)?;
+    let claimable = CLAIMABLE
+        .prefix(&payee)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| coin(amount.u128(), denom)))
+        .collect::<StdResult<Vec<_>>>()?;
 
-    // Update storage
-    CURVE_MINTED.save(deps.storage, "total", &(current_curve_minted + 1))?;
-    
-    // Mint via cw721 base
-    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
-    cw721_contract.mint(deps, env, info, token_id, token_info)?;
+    Ok(ClaimableResponse { address, claimable })
+}
 
-    Ok(Response::new()
-        .add_attribute("method", "mint_curve")
-        .add_attribute("token_id", token_id)
-        .add_attribute("price", price))
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let AdminResponse { admin } = ADMIN.query_admin(deps)?;
+
+    Ok(ConfigResponse {
+        admin,
+        paused: PAUSED.load(deps.storage)?,
+        supply: SUPPLY_CONFIG.load(deps.storage)?,
+        default_royalty: DEFAULT_ROYALTY.may_load(deps.storage)?,
+        soulbound_contributions: SOULBOUND_CONTRIBUTIONS.load(deps.storage)?,
+        platform_fee: PLATFORM_FEE.may_load(deps.storage)?,
+        governance: GOVERNANCE_CONFIG.load(deps.storage)?,
+        pricing: PRICING.may_load(deps.storage)?,
+        voucher_collection: VOUCHER_COLLECTION.may_load(deps.storage)?.unwrap_or_default(),
+    })
 }
 
-pub fn execute_mint_contribution(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    proof: Vec<String>,
-    contribution_points: Uint128,
-) -> Result<Response, ContractError> {
-    let current_contribution_minted = CONTRIBUTION_MINTED.load(deps.storage, "total")?;
-    
-    if current_contribution_minted >= CONTRIBUTION_SUPPLY {
-        return Err(ContractError::ContributionSupplyExhausted {});
+fn query_simulate_mint(deps: Deps, env: Env, count: u32) -> StdResult<SimulateMintResponse> {
+    if count > MAX_BATCH_MINT {
+        return Err(StdError::generic_err(format!("Cannot simulate more than {} mints", MAX_BATCH_MINT)));
     }
 
-    // Verify contribution proof (simplified - in production use proper merkle verification)
-    let proof_hash = format!("{:?}", proof);
-    if CONTRIBUTION_PROOFS.load(deps.storage, &proof_hash).unwrap_or(false) {
-        return Err(ContractError::ProofAlreadyUsed {});
+    let curve_minted = CURVE_MINTED.load(deps.storage)?;
+    if curve_minted + count > SUPPLY_CONFIG.load(deps.storage)?.curve_supply {
+        return Err(StdError::generic_err("Curve supply exhausted"));
     }
 
-    // Check minimum contribution points (e.g., 100 points for Bronze tier)
-    if contribution_points < Uint128::from(100u128) {
-        return Err(ContractError::InsufficientContributionPoints { 
-            required: Uint128::from(100u128),
-            provided: contribution_points 
-        });
-    }
+    let phase = active_phase(MINT_PHASES.load(deps.storage)?, env.block.time);
+    quote_mint(
+        &CURVE_CONFIG.load(deps.storage)?,
+        &SALE_MODE.load(deps.storage)?,
+        phase.as_ref(),
+        curve_minted,
+        count,
+        env.block.time,
+    )
+}
 
-    // Mark proof as used
-    CONTRIBUTION_PROOFS.save(deps.storage, &proof_hash, &true)?;
+fn query_quote_payment(deps: Deps, env: Env, denom: String, count: u32) -> StdResult<PaymentQuoteResponse> {
+    let curve_config = CURVE_CONFIG.load(deps.storage)?;
+    let rate = oracle_rate(deps, &env, &curve_config, &denom)
+        .map_err(|err| StdError::generic_err(err.to_string()))?
+        .map(|rate| rate.rate);
+    let quote = PaymentQuote { denom, rate };
 
-    // Determine tier based on contribution points
-    let tier = if contribution_points >= Uint128::from(10000u128) {
-        "gold"
-    } else if contribution_points >= Uint128::from(1000u128) {
-        "silver"
-    } else {
-        "bronze"
-    };
+    let simulation = query_simulate_mint(deps, env, count)?;
+    let prices = simulation
+        .prices
+        .iter()
+        .map(|price| quote.amount(*price))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let total_price = prices
+        .iter()
+        .try_fold(Uint128::zero(), |total, price| total.checked_add(*price))?;
 
-    // Mint NFT
-    let token_id = format!("contribution-{}", current_contribution_minted + 1);
-    let token_uri = format!("ipfs://digm-{}-{}", tier, current_contribution_minted + 1);
-    
-    let token_info = TokenInfo {
-        owner: deps.api.addr_validate(&info.sender.to_string())?,
-        approvals: vec![],
-        token_uri: Some(token_uri),
-        extension: ContractExtension {
-            tier: Some(tier.to_string()),
-            contribution_points: Some(contribution_points),
-        },
+    Ok(PaymentQuoteResponse {
+        denom: quote.denom,
+        rate: quote.rate,
+        total_price,
+        prices,
+    })
+}
+
+fn query_auction(deps: Deps, env: Env) -> StdResult<AuctionResponse> {
+    let config = match SALE_MODE.load(deps.storage)? {
+        SaleMode::DutchAuction(auction) => auction,
+        SaleMode::BondingCurve {} => return Err(StdError::generic_err("No Dutch auction configured")),
     };
+    let auction_id = AUCTION_ID.load(deps.storage)?;
 
-    // Update storage
-    CONTRIBUTION_MINTED.save(deps.storage, "total", &(current_contribution_minted + 1))?;
-    
-    // Mint via cw721 base
-    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
-    cw721_contract.mint(deps, env, info, token_id, token_info)?;
+    Ok(AuctionResponse {
+        auction_id,
+        current_price: config.price_at(env.block.time),
+        floor_reached_at: config.floor_reached_at(),
+        state: AUCTIONS.load(deps.storage, auction_id)?,
+        schedule: config.schedule(),
+        config,
+    })
+}
 
-    Ok(Response::new()
-        .add_attribute("method", "mint_contribution")
-        .add_attribute("token_id", token_id)
-        .add_attribute("tier", tier)
-        .add_attribute("contribution_points", contribution_points))
+fn query_rebate(deps: Deps, auction_id: u64, address: String) -> StdResult<RebateResponse> {
+    let buyer = deps.api.addr_validate(&address)?;
+    let state = AUCTIONS.load(deps.storage, auction_id)?;
+    let (count, paid) = match AUCTION_PURCHASES.may_load(deps.storage, (auction_id, &buyer))? {
+        Some(purchase) => (purchase.count, purchase.paid),
+        None => (0, Uint128::zero()),
+    };
+    let rebate = state
+        .clearing_price
+        .map(|clearing_price| -> StdResult<_> {
+            Ok(paid.saturating_sub(clearing_price.checked_mul(Uint128::from(count))?))
+        })
+        .transpose()?;
+
+    Ok(RebateResponse {
+        auction_id,
+        address,
+        count,
+        paid,
+        rebate,
+    })
 }
 
-fn calculate_curve_price(current_supply: u32) -> Result<Uint128, ContractError> {
-    // Simple linear bonding curve: price = base_price + (rate * current_supply)
-    // In production, use exponential or more sophisticated curves
-    let base_price = Uint128::from(1000000u128); // 0.001 tokens
-    let rate = Uint128::from(50000u128); // 0.00005 tokens per NFT
-    
-    let price = base_price + (rate * Uint128::from(current_supply as u128));
-    Ok(price)
+fn query_mint_phases(deps: Deps, env: Env) -> StdResult<MintPhasesResponse> {
+    let phases = MINT_PHASES.load(deps.storage)?;
+    let active = active_phase(phases.clone(), env.block.time).map(|phase| phase.name);
+
+    Ok(MintPhasesResponse { phases, active })
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::GetCurvePrice {} => to_binary(&query_curve_price(deps)?),
-        QueryMsg::GetCurveSupply {} => to_binary(&query_curve_supply(deps)?),
-        QueryMsg::GetContributionSupply {} => to_binary(&query_contribution_supply(deps)?),
-        // Delegate other queries to cw721 base
-        _ => {
-            let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
-            cw721_contract.query(deps, _env, msg.into())
-        }
-    }
+fn query_phase_mints(deps: Deps, phase: String, address: String) -> StdResult<PhaseMintsResponse> {
+    let minter = deps.api.addr_validate(&address)?;
+    let max_per_address = MINT_PHASES
+        .load(deps.storage)?
+        .into_iter()
+        .find(|candidate| candidate.name == phase)
+        .and_then(|candidate| candidate.max_per_address);
+
+    Ok(PhaseMintsResponse {
+        minted: PHASE_MINTS.may_load(deps.storage, (&phase, &minter))?.unwrap_or_default(),
+        phase,
+        address,
+        max_per_address,
+    })
 }
 
-fn query_curve_price(deps: Deps) -> StdResult<CurveInfoResponse> {
-    let curve_minted = CURVE_MINTED.load(deps.storage, "total")?;
-    let contribution_minted = CONTRIBUTION_MINTED.load(deps.storage, "total")?;
-    let current_price = calculate_curve_price(curve_minted)?;
-    
-    Ok(CurveInfoResponse {
-        current_price,
-        curve_minted,
-        contribution_minted,
-        total_minted: curve_minted + contribution_minted,
-        max_supply: TOTAL_SUPPLY,
+fn query_reserve(deps: Deps) -> StdResult<ReserveResponse> {
+    Ok(ReserveResponse {
+        denom: CURVE_CONFIG.load(deps.storage)?.denom,
+        reserve: CURVE_RESERVE.load(deps.storage)?,
     })
 }
 
+fn query_backing(deps: Deps, token_id: String) -> StdResult<BackingResponse> {
+    let backing = CURVE_BACKING.may_load(deps.storage, &token_id)?.unwrap_or_default();
+    Ok(BackingResponse { token_id, backing })
+}
+
 fn query_curve_supply(deps: Deps) -> StdResult<u32> {
-    CURVE_MINTED.load(deps.storage, "total")
+    CURVE_MINTED.load(deps.storage)
 }
 
 fn query_contribution_supply(deps: Deps) -> StdResult<u32> {
-    CONTRIBUTION_MINTED.load(deps.storage, "total")
+    CONTRIBUTION_MINTED.load(deps.storage)
+}
+
+fn query_royalty_info(deps: Deps, token_id: String, sale_price: Uint128) -> StdResult<RoyaltiesInfoResponse> {
+    let cw721_contract = Cw721Contract::<ContractExtension, Empty>::default();
+    let token = cw721_contract.tokens.load(deps.storage, &token_id)?;
+
+    // Token override first, then the collection default
+    let royalty = match token.extension.royalty {
+        Some(royalty) => Some(royalty),
+        None => DEFAULT_ROYALTY.may_load(deps.storage)?,
+    };
+
+    Ok(match royalty {
+        Some(royalty) => RoyaltiesInfoResponse {
+            royalty_amount: royalty.amount(sale_price),
+            address: royalty.payment_address,
+        },
+        None => RoyaltiesInfoResponse {
+            address: String::new(),
+            royalty_amount: Uint128::zero(),
+        },
+    })
+}
+
+fn query_check_royalties(deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
+    let royalty_payments = DEFAULT_ROYALTY.may_load(deps.storage)?.is_some()
+        || ROYALTY_OVERRIDES.may_load(deps.storage)?.unwrap_or_default() > 0;
+    Ok(CheckRoyaltiesResponse { royalty_payments })
+}
+
+fn query_signers(deps: Deps) -> StdResult<SignersResponse> {
+    let signers = SIGNERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, signer)| signer))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SignersResponse { signers })
+}
+
+fn query_contribution_root(deps: Deps) -> StdResult<ContributionRootResponse> {
+    Ok(ContributionRootResponse {
+        root: CONTRIBUTION_ROOT.may_load(deps.storage)?,
+    })
 }
 
 // Contract extension for DIGM-specific metadata
@@ -294,6 +4143,16 @@ fn query_contribution_supply(deps: Deps) -> StdResult<u32> {
 pub struct ContractExtension {
     pub tier: Option<String>,
     pub contribution_points: Option<Uint128>,
+    pub royalty: Option<Royalty>,
+    // Album the token licenses, independent of the metadata it displays
+    pub album_id: Option<String>,
+    pub album: Option<AlbumMetadata>,
+    // Rejects transfers, sends and approvals, only the admin can recover the token
+    #[serde(default)]
+    pub soulbound: bool,
+    // Set when the admin clears the license scope, the token then licenses no album at all
+    #[serde(default)]
+    pub license_revoked: bool,
 }
 
 impl Default for ContractExtension {
@@ -301,7 +4160,82 @@ impl Default for ContractExtension {
         Self {
             tier: None,
             contribution_points: None,
+            royalty: None,
+            album_id: None,
+            album: None,
+            soulbound: false,
+            license_revoked: false,
+        }
+    }
+}
+
+impl ContractExtension {
+    // Tokens minted before license scopes existed license the album they were minted with,
+    // unless the admin has revoked the license since
+    pub fn license_album_id(&self) -> Option<&str> {
+        if self.license_revoked {
+            return None;
+        }
+        self.album_id
+            .as_deref()
+            .or_else(|| self.album.as_ref().map(|album| album.album_id.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AlbumMetadata {
+    pub album_id: String,
+    pub title: String,
+    pub tracks: Vec<TrackMetadata>,
+    // Hex-encoded sha256 Merkle root over the album's audio content
+    pub content_root: String,
+    pub cover_hash: String,
+    pub artist: String,
+    pub license_terms: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TrackMetadata {
+    pub title: String,
+    pub duration_secs: u32,
+    pub content_hash: String,
+}
+
+impl AlbumMetadata {
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        if self.album_id.is_empty() {
+            return Err(ContractError::InvalidMetadata {
+                reason: "album_id cannot be empty".to_string(),
+            });
+        }
+        if self.tracks.is_empty() {
+            return Err(ContractError::InvalidMetadata {
+                reason: "album needs at least one track".to_string(),
+            });
+        }
+        api.addr_validate(&self.artist)?;
+        validate_merkle_root(&self.content_root)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Royalty {
+    pub payment_address: String,
+    pub bps: u64,
+}
+
+impl Royalty {
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        api.addr_validate(&self.payment_address)?;
+        if self.bps > BPS_DENOMINATOR {
+            return Err(ContractError::InvalidRoyalty { bps: self.bps });
         }
+        Ok(())
+    }
+
+    pub fn amount(&self, sale_price: Uint128) -> Uint128 {
+        sale_price.multiply_ratio(self.bps, BPS_DENOMINATOR)
     }
 }
 
@@ -311,6 +4245,18 @@ pub struct Empty {}
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Minting is paused")]
+    Paused {},
+
     #[error("Curve supply exhausted")]
     CurveSupplyExhausted {},
     
@@ -320,77 +4266,290 @@ pub enum ContractError {
     #[error("Insufficient payment: required {required}")]
     InsufficientPayment { required: Uint128 },
     
+    #[error("Batch size must be between 1 and {max}")]
+    InvalidBatchSize { max: u32 },
+    
+    #[error("Slippage exceeded: total price {total_price} above max {max_total_price}")]
+    SlippageExceeded { total_price: Uint128, max_total_price: Uint128 },
+    
+    #[error("Minting is closed")]
+    MintingClosed {},
+    
+    #[error("Sender is not on the allowlist for this phase")]
+    NotAllowlisted {},
+    
+    #[error("Phase {phase} allows {max_per_address} mints per address")]
+    PhaseLimitReached { phase: String, max_per_address: u32 },
+    
+    #[error("Invalid mint phase: {reason}")]
+    InvalidPhase { reason: String },
+    
+    #[error("No funds sent")]
+    NoFunds {},
+    
+    #[error("Payment must be a single coin")]
+    MultipleDenoms {},
+    
+    #[error("Invalid payment denom: expected {expected}, received {received}")]
+    InvalidDenom { expected: String, received: String },
+    
+    #[error("Invalid bridge data: {reason}")]
+    InvalidBridgeData { reason: String },
+    
+    #[error("Only voucher collections mirror tokens from other chains")]
+    NotVoucherCollection {},
+    
+    #[error("Voucher collections only hold tokens minted by the bridge")]
+    VoucherCollection {},
+    
+    #[error("Token id {token_id} is reserved for curve and contribution mints")]
+    ReservedTokenId { token_id: String },
+    
+    #[error("Invalid pricing config: {reason}")]
+    InvalidPricing { reason: String },
+    
+    #[error("Oracle rate for {denom} is {age} seconds old")]
+    StalePrice { denom: String, age: u64 },
+    
+    #[error("Oracle rate for {denom} moved more than allowed since the last mint")]
+    PriceDeviation { denom: String },
+    
+    #[error("Oracle returned no rate for {denom}")]
+    InvalidOraclePrice { denom: String },
+    
+    #[error("Price {price} overflows once converted to {denom}")]
+    ConversionOverflow { price: Uint128, denom: String },
+    
+    #[error("Rebate auctions are only paid in {denom} without conversion")]
+    RebateDenom { denom: String },
+    
+    #[error("Invalid auction: {reason}")]
+    InvalidAuction { reason: String },
+    
+    #[error("No Dutch auction configured")]
+    NoAuction {},
+    
+    #[error("Auction has not started")]
+    AuctionNotStarted {},
+    
+    #[error("Auction has not reached its floor price")]
+    AuctionNotEnded {},
+    
+    #[error("Auction {auction_id} is settled")]
+    AuctionSettled { auction_id: u64 },
+    
+    #[error("Auction {auction_id} is not settled")]
+    AuctionNotSettled { auction_id: u64 },
+    
+    #[error("Sell-backs are disabled during an auction")]
+    SellBackDisabled {},
+    
+    #[error("Message is not a cw721 message")]
+    NotCw721Message {},
+    
+    #[error("Rental expiry must be in the future")]
+    RentalExpired {},
+    
+    #[error("Token {token_id} is soulbound")]
+    Soulbound { token_id: String },
+    
+    #[error("Token {token_id} is not soulbound")]
+    NotSoulbound { token_id: String },
+    
+    #[error("Token {token_id} was not minted on the curve")]
+    NotCurveToken { token_id: String },
+    
+    #[error("Minted count is already zero")]
+    MintedCountUnderflow {},
+    
+    #[error("Token {token_id} was not minted from the sender's contribution claim")]
+    NotClaimant { token_id: String },
+    
+    #[error("Token {token_id} has no reserve backing to redeem")]
+    NotBacked { token_id: String },
+    
+    #[error("Supply cap cannot be lower than the amount already minted")]
+    SupplyBelowMinted {},
+    
+    #[error("Attestation expired")]
+    AttestationExpired {},
+    
+    #[error("Signature does not match any registered signer")]
+    InvalidSignature {},
+    
+    #[error("Invalid signer public key for its scheme")]
+    InvalidSigner {},
+    
+    #[error("Nonce {nonce} already used by this signer")]
+    NonceAlreadyUsed { nonce: u64 },
+    
+    #[error("Token {token_id} was not minted from a contribution")]
+    NotContributionToken { token_id: String },
+    
+    #[error("New points do not raise the token above {current}")]
+    NoTierUpgrade { current: String },
+    
+    #[error("Invalid metadata: {reason}")]
+    InvalidMetadata { reason: String },
+    
+    #[error("Metadata is frozen")]
+    MetadataFrozen {},
+    
+    #[error("Invalid payees: {reason}")]
+    InvalidPayees { reason: String },
+    
+    #[error("Invalid platform fee: {bps} bps exceeds 10000")]
+    InvalidPlatformFee { bps: u64 },
+    
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+    
+    #[error("Invalid governance config: {reason}")]
+    InvalidGovernanceConfig { reason: String },
+    
+    #[error("Invalid proposal: {reason}")]
+    InvalidProposal { reason: String },
+    
+    #[error("No voting power at the proposal snapshot")]
+    NoVotingPower {},
+    
+    #[error("Proposal {proposal_id} not found")]
+    ProposalNotFound { proposal_id: u64 },
+    
+    #[error("Proposal {proposal_id} is closed")]
+    ProposalClosed { proposal_id: u64 },
+    
+    #[error("Voting on proposal {proposal_id} has ended")]
+    VotingEnded { proposal_id: u64 },
+    
+    #[error("Voting on proposal {proposal_id} has not ended")]
+    VotingNotEnded { proposal_id: u64 },
+    
+    #[error("Already voted on proposal {proposal_id}")]
+    AlreadyVoted { proposal_id: u64 },
+    
+    #[error("Invalid migration: {reason}")]
+    InvalidMigration { reason: String },
+    
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+    
+    #[error("Token {token_id} not found")]
+    TokenNotFound { token_id: String },
+    
+    #[error("Invalid royalty: {bps} bps exceeds 10000")]
+    InvalidRoyalty { bps: u64 },
+    
     #[error("Proof already used")]
     ProofAlreadyUsed {},
     
     #[error("Insufficient contribution points: required {required}, provided {provided}")]
     InsufficientContributionPoints { required: Uint128, provided: Uint128 },
     
+    #[error("Invalid curve config: {reason}")]
+    InvalidCurveConfig { reason: String },
+    
+    #[error("Contribution Merkle root not set")]
+    ContributionRootNotSet {},
+    
+    #[error("Invalid contribution proof")]
+    InvalidProof {},
+    
+    #[error("Invalid hex encoding: {0}")]
+    Hex(#[from] FromHexError),
+    
     #[error("CW721 error: {0}")]
     Cw721Error(#[from] Cw721Error),
 }
 
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
+
 impl From<ContractError> for Cw721Error {
     fn from(err: ContractError) -> Self {
         Cw721Error::Custom(err.to_string())
     }
 }
 
-// Implement conversions for cw721 messages
-impl From<ExecuteMsg> for cw721_base::ExecuteMsg<ContractExtension, Empty> {
-    fn from(msg: ExecuteMsg) -> Self {
+// Conversions for the messages delegated to cw721 base
+impl TryFrom<ExecuteMsg> for cw721_base::ExecuteMsg<ContractExtension, Empty> {
+    type Error = ContractError;
+
+    fn try_from(msg: ExecuteMsg) -> Result<Self, Self::Error> {
         match msg {
+            ExecuteMsg::UpdateOwnership(action) => Ok(cw721_base::ExecuteMsg::UpdateOwnership(action)),
             ExecuteMsg::TransferNft { recipient, token_id } => {
-                cw721_base::ExecuteMsg::TransferNft { recipient, token_id }
+                Ok(cw721_base::ExecuteMsg::TransferNft { recipient, token_id })
             }
             ExecuteMsg::SendNft { contract, token_id, msg } => {
-                cw721_base::ExecuteMsg::SendNft { contract, token_id, msg }
+                Ok(cw721_base::ExecuteMsg::SendNft { contract, token_id, msg })
             }
             ExecuteMsg::Approve { spender, token_id, expires } => {
-                cw721_base::ExecuteMsg::Approve { spender, token_id, expires }
+                Ok(cw721_base::ExecuteMsg::Approve { spender, token_id, expires })
             }
             ExecuteMsg::Revoke { spender, token_id } => {
-                cw721_base::ExecuteMsg::Revoke { spender, token_id }
+                Ok(cw721_base::ExecuteMsg::Revoke { spender, token_id })
             }
             ExecuteMsg::ApproveAll { operator, expires } => {
-                cw721_base::ExecuteMsg::ApproveAll { operator, expires }
+                Ok(cw721_base::ExecuteMsg::ApproveAll { operator, expires })
             }
             ExecuteMsg::RevokeAll { operator } => {
-                cw721_base::ExecuteMsg::RevokeAll { operator }
+                Ok(cw721_base::ExecuteMsg::RevokeAll { operator })
             }
-            _ => panic!("Invalid conversion"),
+            ExecuteMsg::Mint { token_id, owner, token_uri, extension } => Ok(cw721_base::ExecuteMsg::Mint {
+                token_id,
+                owner,
+                token_uri,
+                extension: extension.unwrap_or_default(),
+            }),
+            ExecuteMsg::Burn { token_id } => Ok(cw721_base::ExecuteMsg::Burn { token_id }),
+            ExecuteMsg::Extension { msg } => Ok(cw721_base::ExecuteMsg::Extension { msg }),
+            _ => Err(ContractError::NotCw721Message {}),
         }
     }
 }
 
-impl From<QueryMsg> for cw721_base::QueryMsg<ContractExtension> {
-    fn from(msg: QueryMsg) -> Self {
+impl TryFrom<QueryMsg> for cw721_base::QueryMsg<ContractExtension> {
+    type Error = ContractError;
+
+    fn try_from(msg: QueryMsg) -> Result<Self, Self::Error> {
         match msg {
             QueryMsg::OwnerOf { token_id, include_expired } => {
-                cw721_base::QueryMsg::OwnerOf { token_id, include_expired }
+                Ok(cw721_base::QueryMsg::OwnerOf { token_id, include_expired })
             }
             QueryMsg::Approval { token_id, spender, include_expired } => {
-                cw721_base::QueryMsg::Approval { token_id, spender, include_expired }
+                Ok(cw721_base::QueryMsg::Approval { token_id, spender, include_expired })
             }
-            QueryMsg::ApprovalsFor { token_id, include_expired } => {
-                cw721_base::QueryMsg::ApprovalsFor { token_id, include_expired }
+            QueryMsg::Approvals { token_id, include_expired } => {
+                Ok(cw721_base::QueryMsg::Approvals { token_id, include_expired })
+            }
+            QueryMsg::Operator { owner, operator, include_expired } => {
+                Ok(cw721_base::QueryMsg::Operator { owner, operator, include_expired })
             }
             QueryMsg::AllOperators { owner, include_expired, start_after, limit } => {
-                cw721_base::QueryMsg::AllOperators { owner, include_expired, start_after, limit }
+                Ok(cw721_base::QueryMsg::AllOperators { owner, include_expired, start_after, limit })
             }
-            QueryMsg::AllTokens { start_after, limit } => {
-                cw721_base::QueryMsg::AllTokens { start_after, limit }
+            QueryMsg::NumTokens {} => Ok(cw721_base::QueryMsg::NumTokens {}),
+            QueryMsg::ContractInfo {} => Ok(cw721_base::QueryMsg::ContractInfo {}),
+            QueryMsg::NftInfo { token_id } => Ok(cw721_base::QueryMsg::NftInfo { token_id }),
+            QueryMsg::AllNftInfo { token_id, include_expired } => {
+                Ok(cw721_base::QueryMsg::AllNftInfo { token_id, include_expired })
             }
             QueryMsg::Tokens { owner, start_after, limit } => {
-                cw721_base::QueryMsg::Tokens { owner, start_after, limit }
+                Ok(cw721_base::QueryMsg::Tokens { owner, start_after, limit })
             }
-            QueryMsg::ContractInfo {} => {
-                cw721_base::QueryMsg::ContractInfo {}
-            }
-            QueryMsg::NumTokens {} => {
-                cw721_base::QueryMsg::NumTokens {}
+            QueryMsg::AllTokens { start_after, limit } => {
+                Ok(cw721_base::QueryMsg::AllTokens { start_after, limit })
             }
-            _ => panic!("Invalid conversion"),
+            QueryMsg::Minter {} => Ok(cw721_base::QueryMsg::Minter {}),
+            QueryMsg::Ownership {} => Ok(cw721_base::QueryMsg::Ownership {}),
+            _ => Err(ContractError::NotCw721Message {}),
         }
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,2398 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    coin, coins, from_binary, to_binary, wasm_execute, Attribute, BankMsg, Coin, ContractResult, CosmosMsg,
+    Decimal, OwnedDeps, Response, StdError, SystemResult, Uint128, WasmMsg,
+};
+use cw721::{AllNftInfoResponse, Cw721ReceiveMsg, NftInfoResponse, NumTokensResponse, OwnerOfResponse};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+
+use crate::*;
+
+const ADMIN_ADDR: &str = "admin";
+const DENOM: &str = "ujuno";
+
+type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: "Digm".to_string(),
+        symbol: "DIGM".to_string(),
+        minter: ADMIN_ADDR.to_string(),
+        admin: None,
+        curve_start_price: Uint128::new(1_000_000),
+        curve_rate: Uint128::new(1_000),
+        curve_type: None,
+        curve_denom: DENOM.to_string(),
+        sell_spread_bps: None,
+        contribution_root: None,
+        royalty: None,
+        payees: None,
+        base_uri: None,
+        phases: None,
+        sale_mode: None,
+        album: None,
+        soulbound_contributions: None,
+        supply: None,
+        platform_fee: None,
+        governance: None,
+        pricing: None,
+    }
+}
+
+fn setup_with(msg: InstantiateMsg) -> TestDeps {
+    let mut deps = mock_dependencies();
+    instantiate(deps.as_mut(), mock_env(), mock_info(ADMIN_ADDR, &[]), msg).unwrap();
+    deps
+}
+
+#[test]
+fn exponential_price_overflow_is_an_error() {
+    let deps = setup_with(InstantiateMsg {
+        curve_start_price: Uint128::MAX,
+        curve_type: Some(CurveType::Exponential {
+            growth: Decimal::percent(10),
+        }),
+        ..instantiate_msg()
+    });
+
+    let err = query(deps.as_ref(), mock_env(), QueryMsg::SimulateMint { count: 2 }).unwrap_err();
+    assert!(matches!(err, StdError::GenericErr { .. }));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::SimulateMint { count: 1 }).unwrap();
+    let simulated: SimulateMintResponse = from_binary(&res).unwrap();
+    assert_eq!(simulated.total_price, Uint128::MAX);
+}
+
+fn setup() -> TestDeps {
+    setup_with(instantiate_msg())
+}
+
+fn mint_curve(deps: &mut TestDeps, sender: &str, amount: u128) -> String {
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &coins(amount, DENOM)),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+    .unwrap();
+    res.attributes
+        .iter()
+        .find(|attr| attr.key == "token_id")
+        .map(|attr| attr.value.clone())
+        .unwrap()
+}
+
+fn backing(deps: &TestDeps, token_id: &str) -> Uint128 {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::GetBacking { token_id: token_id.to_string() },
+    )
+    .unwrap();
+    from_binary::<BackingResponse>(&res).unwrap().backing
+}
+
+fn reserve(deps: &TestDeps) -> Uint128 {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReserve {}).unwrap();
+    from_binary::<ReserveResponse>(&res).unwrap().reserve
+}
+
+fn claimable(deps: &TestDeps, address: &str) -> Vec<Coin> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::GetClaimable { address: address.to_string() },
+    )
+    .unwrap();
+    from_binary::<ClaimableResponse>(&res).unwrap().claimable
+}
+
+#[test]
+fn sell_back_pays_the_curve_price_less_the_spread() {
+    let mut deps = setup();
+    let first = mint_curve(&mut deps, "alice", 1_000_000);
+    let second = mint_curve(&mut deps, "bob", 1_001_000);
+
+    // 10% sell spread on the price each token paid, the rest is revenue
+    assert_eq!(backing(&deps, &first), Uint128::new(900_000));
+    assert_eq!(backing(&deps, &second), Uint128::new(900_900));
+    assert_eq!(reserve(&deps), Uint128::new(1_800_900));
+    assert_eq!(claimable(&deps, ADMIN_ADDR), coins(200_100, DENOM));
+
+    // Whichever token is sold, the top slot's sell price is paid
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::SellCurve { token_id: first.clone() },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: coins(900_900, DENOM),
+        })
+    );
+    assert_eq!(reserve(&deps), Uint128::new(900_000));
+    assert_eq!(backing(&deps, &first), Uint128::zero());
+
+    // A burned token's backing goes to the payees rather than staying in the reserve
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::Burn { token_id: second },
+    )
+    .unwrap();
+    assert_eq!(reserve(&deps), Uint128::zero());
+    assert_eq!(claimable(&deps, ADMIN_ADDR), coins(1_100_100, DENOM));
+}
+
+fn check_royalties(deps: &TestDeps) -> bool {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Extension { msg: Cw2981QueryMsg::CheckRoyalties {} },
+    )
+    .unwrap();
+    from_binary::<CheckRoyaltiesResponse>(&res).unwrap().royalty_payments
+}
+
+#[test]
+fn check_royalties_follows_stored_royalties() {
+    let mut deps = setup();
+    assert!(!check_royalties(&deps));
+
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    let set_royalty = |royalty| ExecuteMsg::SetTokenRoyalty {
+        token_id: token_id.clone(),
+        royalty,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        set_royalty(Some(Royalty {
+            payment_address: "artist".to_string(),
+            bps: 500,
+        })),
+    )
+    .unwrap();
+    assert!(check_royalties(&deps));
+
+    execute(deps.as_mut(), mock_env(), mock_info(ADMIN_ADDR, &[]), set_royalty(None)).unwrap();
+    assert!(!check_royalties(&deps));
+
+    let deps = setup_with(InstantiateMsg {
+        royalty: Some(Royalty {
+            payment_address: "artist".to_string(),
+            bps: 500,
+        }),
+        ..instantiate_msg()
+    });
+    assert!(check_royalties(&deps));
+}
+
+fn royalty_info(deps: &TestDeps, token_id: &str) -> RoyaltiesInfoResponse {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Extension {
+            msg: Cw2981QueryMsg::RoyaltyInfo {
+                token_id: token_id.to_string(),
+                sale_price: Uint128::new(10_000),
+            },
+        },
+    )
+    .unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn royalty_info_prefers_the_token_override_to_the_collection_default() {
+    let mut deps = setup_with(InstantiateMsg {
+        royalty: Some(Royalty {
+            payment_address: "label".to_string(),
+            bps: 500,
+        }),
+        ..instantiate_msg()
+    });
+    let first = mint_curve(&mut deps, "alice", 1_000_000);
+    let second = mint_curve(&mut deps, "bob", 1_001_000);
+    let set_royalty = |deps: &mut TestDeps, royalty| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN_ADDR, &[]),
+            ExecuteMsg::SetTokenRoyalty { token_id: first.clone(), royalty },
+        )
+        .unwrap();
+    };
+    let label = RoyaltiesInfoResponse {
+        address: "label".to_string(),
+        royalty_amount: Uint128::new(500),
+    };
+    assert_eq!(royalty_info(&deps, &first), label);
+
+    set_royalty(
+        &mut deps,
+        Some(Royalty {
+            payment_address: "artist".to_string(),
+            bps: 1_000,
+        }),
+    );
+    assert_eq!(
+        royalty_info(&deps, &first),
+        RoyaltiesInfoResponse {
+            address: "artist".to_string(),
+            royalty_amount: Uint128::new(1_000),
+        }
+    );
+    assert_eq!(royalty_info(&deps, &second), label);
+
+    // Clearing the override falls back to the default
+    set_royalty(&mut deps, None);
+    assert_eq!(royalty_info(&deps, &first), label);
+
+    // Without either nothing is owed
+    let mut deps = setup();
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    assert_eq!(
+        royalty_info(&deps, &token_id),
+        RoyaltiesInfoResponse {
+            address: String::new(),
+            royalty_amount: Uint128::zero(),
+        }
+    );
+}
+
+#[test]
+fn max_supply_saturates() {
+    let deps = setup_with(InstantiateMsg {
+        supply: Some(SupplyConfig {
+            curve_supply: u32::MAX,
+            contribution_supply: 1,
+        }),
+        ..instantiate_msg()
+    });
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetCurvePrice {}).unwrap();
+    let info: CurveInfoResponse = from_binary(&res).unwrap();
+    assert_eq!(info.max_supply, u32::MAX);
+}
+
+// State as 0.1.x left it: counters under "total", proof-keyed claims and tier-only extensions
+// Token extension as 0.1.0 stored it, before royalties, albums and soulbound tokens
+#[derive(Serialize, Deserialize, Clone)]
+struct LegacyExtension {
+    tier: Option<String>,
+    contribution_points: Option<Uint128>,
+}
+
+fn legacy_deps() -> TestDeps {
+    let mut deps = mock_dependencies();
+    set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.1.0").unwrap();
+    LEGACY_CURVE_MINTED.save(deps.as_mut().storage, "total", &1).unwrap();
+    LEGACY_CONTRIBUTION_MINTED.save(deps.as_mut().storage, "total", &2).unwrap();
+    LEGACY_CONTRIBUTION_PROOFS
+        .save(deps.as_mut().storage, "[\"ab\"]", &true)
+        .unwrap();
+
+    let cw721_contract = Cw721Contract::<LegacyExtension, Empty>::default();
+    let tokens = [
+        ("curve-1", "alice", None),
+        ("contribution-1", "alice", Some("gold")),
+        ("contribution-2", "bob", Some("silver")),
+    ];
+    for (token_id, owner, tier) in tokens {
+        let token = TokenInfo {
+            owner: Addr::unchecked(owner),
+            approvals: vec![],
+            token_uri: None,
+            extension: LegacyExtension {
+                tier: tier.map(str::to_string),
+                contribution_points: None,
+            },
+        };
+        cw721_contract.tokens.save(deps.as_mut().storage, token_id, &token).unwrap();
+    }
+    let stored = deps.storage.get(&cw721_contract.tokens.key("contribution-1")).unwrap();
+    assert_eq!(
+        String::from_utf8(stored).unwrap(),
+        r#"{"owner":"alice","approvals":[],"token_uri":null,"extension":{"tier":"gold","contribution_points":null}}"#
+    );
+    deps
+}
+
+fn migrate_msg() -> MigrateMsg {
+    MigrateMsg {
+        admin: Some(ADMIN_ADDR.to_string()),
+        curve_denom: Some(DENOM.to_string()),
+    }
+}
+
+fn migrated_deps() -> TestDeps {
+    let mut deps = legacy_deps();
+    migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap();
+    deps
+}
+
+#[test]
+fn migrate_runs_every_step_in_order() {
+    let mut deps = legacy_deps();
+    let res = migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap();
+
+    let steps: Vec<_> = res
+        .attributes
+        .iter()
+        .filter(|attr| attr.key == "migration")
+        .map(|attr| attr.value.clone())
+        .collect();
+    let expected: Vec<_> = MIGRATIONS
+        .iter()
+        .map(|(target, name, _)| format!("{}:{}", target, name))
+        .collect();
+    assert_eq!(steps, expected);
+    assert_eq!(get_contract_version(&deps.storage).unwrap().version, CONTRACT_VERSION);
+
+    // Nothing is left to run once the stored version is current
+    let res = migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "migration"));
+}
+
+#[test]
+fn migrate_rejects_other_contracts_and_downgrades() {
+    let mut deps = legacy_deps();
+    set_contract_version(deps.as_mut().storage, "crates.io:other", "0.1.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidMigration { .. }));
+
+    set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "9.0.0").unwrap();
+    let err = migrate(deps.as_mut(), mock_env(), migrate_msg()).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidMigration { .. }));
+}
+
+#[test]
+fn migrate_counters_moves_legacy_totals() {
+    let deps = migrated_deps();
+    assert_eq!(CURVE_MINTED.load(&deps.storage).unwrap(), 1);
+    assert_eq!(CONTRIBUTION_MINTED.load(&deps.storage).unwrap(), 2);
+    assert_eq!(CURVE_TOKEN_SEQ.load(&deps.storage).unwrap(), 1);
+    assert_eq!(CONTRIBUTION_TOKEN_SEQ.load(&deps.storage).unwrap(), 2);
+    assert!(LEGACY_CURVE_MINTED.may_load(&deps.storage, "total").unwrap().is_none());
+    assert!(LEGACY_CONTRIBUTION_MINTED.may_load(&deps.storage, "total").unwrap().is_none());
+}
+
+#[test]
+fn migrate_curve_needs_a_denom() {
+    let deps = migrated_deps();
+    let curve_config = CURVE_CONFIG.load(&deps.storage).unwrap();
+    assert_eq!(curve_config.denom, DENOM);
+    assert_eq!(curve_config.start_price, Uint128::new(1_000_000));
+    assert_eq!(CURVE_RESERVE.load(&deps.storage).unwrap(), Uint128::zero());
+
+    let mut deps = legacy_deps();
+    let msg = MigrateMsg {
+        curve_denom: None,
+        ..migrate_msg()
+    };
+    let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidMigration { .. }));
+}
+
+#[test]
+fn migrate_admin_needs_an_admin() {
+    let deps = migrated_deps();
+    assert_eq!(ADMIN.get(deps.as_ref()).unwrap(), Some(Addr::unchecked(ADMIN_ADDR)));
+    assert!(!PAUSED.load(&deps.storage).unwrap());
+    assert_eq!(SUPPLY_CONFIG.load(&deps.storage).unwrap().curve_supply, CURVE_SUPPLY);
+
+    let mut deps = legacy_deps();
+    let msg = MigrateMsg {
+        admin: None,
+        ..migrate_msg()
+    };
+    let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidMigration { .. }));
+}
+
+#[test]
+fn migrate_payees_and_metadata_default() {
+    let deps = migrated_deps();
+    assert_eq!(
+        PAYEES.load(&deps.storage).unwrap(),
+        vec![Payee {
+            address: ADMIN_ADDR.to_string(),
+            weight: 1,
+        }]
+    );
+    let metadata_config = METADATA_CONFIG.load(&deps.storage).unwrap();
+    assert_eq!(metadata_config.base_uri, None);
+    assert!(!metadata_config.frozen);
+}
+
+#[test]
+fn migrate_contribution_proofs_drops_legacy_claims() {
+    let deps = migrated_deps();
+    let legacy = LEGACY_CONTRIBUTION_PROOFS
+        .keys(&deps.storage, None, None, Order::Ascending)
+        .count();
+    assert_eq!(legacy, 0);
+}
+
+#[test]
+fn migrate_sale_and_soulbound_default() {
+    let deps = migrated_deps();
+    assert_eq!(SALE_MODE.load(&deps.storage).unwrap(), SaleMode::BondingCurve {});
+    assert!(MINT_PHASES.load(&deps.storage).unwrap().is_empty());
+    assert_eq!(AUCTION_ID.load(&deps.storage).unwrap(), 0);
+    assert_eq!(AUCTION_ESCROW.load(&deps.storage).unwrap(), Uint128::zero());
+    assert!(!SOULBOUND_CONTRIBUTIONS.load(&deps.storage).unwrap());
+
+    // Tokens stored without the newer flags read back transferable and licensed
+    let token = Cw721Contract::<ContractExtension, Empty>::default()
+        .tokens
+        .load(&deps.storage, "contribution-1")
+        .unwrap();
+    assert_eq!(
+        token.extension,
+        ContractExtension { tier: Some("gold".to_string()), ..ContractExtension::default() }
+    );
+}
+
+#[test]
+fn migrate_tier_index_and_governance_backfill() {
+    let deps = migrated_deps();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::TierCounts {}).unwrap();
+    let counts: TierCountsResponse = from_binary(&res).unwrap();
+    let count = |tier: &str| {
+        counts
+            .tiers
+            .iter()
+            .find(|count| count.tier == tier)
+            .map(|count| count.count)
+            .unwrap_or_default()
+    };
+    assert_eq!(count("gold"), 1);
+    assert_eq!(count("silver"), 1);
+
+    assert_eq!(GOVERNANCE_CONFIG.load(&deps.storage).unwrap(), GovernanceConfig::default());
+    assert_eq!(PROPOSAL_COUNT.load(&deps.storage).unwrap(), 0);
+    assert_eq!(voting_power(&deps, "alice", None), (2, 3));
+    assert_eq!(voting_power(&deps, "bob", None), (1, 3));
+}
+
+// (weight, total weight) of `address`, as of the start of `height` when given
+fn voting_power(deps: &TestDeps, address: &str, height: Option<u64>) -> (u64, u64) {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::VotingPower {
+            address: address.to_string(),
+            height,
+        },
+    )
+    .unwrap();
+    let power: VotingPowerResponse = from_binary(&res).unwrap();
+    (power.weight, power.total_weight)
+}
+
+fn leaf(address: &str, points: u128) -> [u8; 32] {
+    sha256(format!("{}{}", address, points).as_bytes())
+}
+
+// Root of a two-leaf tree and the proof of each leaf
+fn merkle_pair(a: [u8; 32], b: [u8; 32]) -> (String, Vec<String>, Vec<String>) {
+    let mut pair = [a, b];
+    pair.sort_unstable();
+    (hex::encode(sha256(&pair.concat())), vec![hex::encode(b)], vec![hex::encode(a)])
+}
+
+fn set_root(deps: &mut TestDeps, root: String) {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::SetContributionRoot { root },
+    )
+    .unwrap();
+}
+
+fn mint_contribution(deps: &mut TestDeps, sender: &str, proof: Vec<String>, points: u128) -> String {
+    let res = try_mint_contribution(deps, sender, proof, points).unwrap();
+    res.attributes
+        .iter()
+        .find(|attr| attr.key == "token_id")
+        .map(|attr| attr.value.clone())
+        .unwrap()
+}
+
+fn transfer(deps: &mut TestDeps, sender: &str, recipient: &str, token_id: &str) {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        },
+    )
+    .unwrap();
+}
+
+fn attestation_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32].into()).unwrap()
+}
+
+fn attestation_signer(key: &SigningKey) -> Signer {
+    Signer {
+        pubkey: Binary::from(key.verifying_key().to_encoded_point(true).as_bytes()),
+        scheme: SignatureScheme::Secp256k1,
+    }
+}
+
+fn sign_attestation(key: &SigningKey, sender: &str, points: u128, nonce: u64, expires: u64) -> Binary {
+    let contract = mock_env().contract.address;
+    let digest = attestation_hash(&contract, &Addr::unchecked(sender), Uint128::new(points), nonce, expires);
+    let signature: Signature = key.sign_prehash(&digest).unwrap();
+    Binary::from(signature.to_vec())
+}
+
+fn update_signers(
+    deps: &mut TestDeps,
+    add: Vec<Signer>,
+    remove: Vec<Binary>,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::UpdateSigners { add, remove },
+    )
+}
+
+fn signed_mint(key: &SigningKey, sender: &str, points: u128, nonce: u64, expires: u64) -> ExecuteMsg {
+    ExecuteMsg::MintContributionSigned {
+        points: Uint128::new(points),
+        nonce,
+        expires,
+        signature: sign_attestation(key, sender, points, nonce, expires),
+    }
+}
+
+#[test]
+fn attestations_mint_and_upgrade_once_per_nonce() {
+    let mut deps = setup();
+    let key = attestation_key();
+    update_signers(&mut deps, vec![attestation_signer(&key)], vec![]).unwrap();
+    let expires = mock_env().block.time.seconds() + 100;
+
+    // Signed for Alice, so worthless to Bob
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        signed_mint(&key, "alice", 1_000, 1, expires),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSignature {});
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        signed_mint(&key, "alice", 1_000, 1, expires),
+    )
+    .unwrap();
+    let token_id = res.attributes.iter().find(|attr| attr.key == "token_id").unwrap().value.clone();
+    assert_eq!(voting_power(&deps, "alice", None), (1, 1));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        signed_mint(&key, "bob", 1_000, 1, expires),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NonceAlreadyUsed { nonce: 1 });
+
+    // A later score raises the tier of the token minted from the first one
+    let upgrade = ExecuteMsg::UpgradeTierSigned {
+        token_id: token_id.clone(),
+        points: Uint128::new(10_000),
+        nonce: 2,
+        expires,
+        signature: sign_attestation(&key, "alice", 10_000, 2, expires),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), upgrade.clone()).unwrap();
+    let token = Cw721Contract::<ContractExtension, Empty>::default()
+        .tokens
+        .load(&deps.storage, &token_id)
+        .unwrap();
+    assert_eq!(token.extension.tier.as_deref(), Some("gold"));
+    assert_eq!(voting_power(&deps, "alice", None), (2, 2));
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), upgrade).unwrap_err();
+    assert_eq!(err, ContractError::NonceAlreadyUsed { nonce: 2 });
+}
+
+#[test]
+fn expired_and_removed_attestations_are_rejected() {
+    let mut deps = setup();
+    let key = attestation_key();
+    let signer = attestation_signer(&key);
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::UpdateSigners { add: vec![signer.clone()], remove: vec![] },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Admin(_)));
+    update_signers(&mut deps, vec![signer.clone()], vec![]).unwrap();
+
+    let expires = mock_env().block.time.seconds() + 100;
+    let err = execute(
+        deps.as_mut(),
+        env_at(100),
+        mock_info("alice", &[]),
+        signed_mint(&key, "alice", 1_000, 1, expires),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AttestationExpired {});
+
+    update_signers(&mut deps, vec![], vec![signer.pubkey]).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        signed_mint(&key, "alice", 1_000, 1, expires),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSignature {});
+}
+
+fn tokens_by_tier(deps: &TestDeps, tier: &str, start_after: Option<&str>, limit: Option<u32>) -> Vec<String> {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::TokensByTier {
+            tier: tier.to_string(),
+            start_after: start_after.map(str::to_string),
+            limit,
+        },
+    )
+    .unwrap();
+    from_binary::<TokensByTierResponse>(&res).unwrap().tokens
+}
+
+fn tier_counts(counts: &[(&str, u32)]) -> Vec<TierCount> {
+    counts.iter().map(|(tier, count)| TierCount { tier: tier.to_string(), count: *count }).collect()
+}
+
+fn owner_tier_summary(deps: &TestDeps, owner: &str) -> OwnerTierSummaryResponse {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::OwnerTierSummary { owner: owner.to_string() },
+    )
+    .unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn tier_index_pages_and_follows_upgrades_and_burns() {
+    let mut deps = setup();
+    let key = attestation_key();
+    update_signers(&mut deps, vec![attestation_signer(&key)], vec![]).unwrap();
+    let expires = mock_env().block.time.seconds() + 100;
+    for (nonce, (sender, points)) in
+        [("alice", 100), ("bob", 100), ("carol", 100), ("dave", 100), ("erin", 1_000)].into_iter().enumerate()
+    {
+        let msg = signed_mint(&key, sender, points, nonce as u64, expires);
+        execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg).unwrap();
+    }
+    // Curve tokens have no tier and stay out of the index
+    mint_curve(&mut deps, "alice", 1_000_000);
+
+    assert_eq!(tokens_by_tier(&deps, "bronze", None, Some(2)), vec!["contribution-1", "contribution-2"]);
+    assert_eq!(
+        tokens_by_tier(&deps, "bronze", Some("contribution-2"), Some(2)),
+        vec!["contribution-3", "contribution-4"]
+    );
+    assert_eq!(tokens_by_tier(&deps, "bronze", Some("contribution-4"), None), Vec::<String>::new());
+    assert_eq!(tokens_by_tier(&deps, "silver", None, None), vec!["contribution-5"]);
+
+    transfer(&mut deps, "bob", "alice", "contribution-2");
+    transfer(&mut deps, "carol", "alice", "contribution-3");
+    assert_eq!(
+        owner_tier_summary(&deps, "alice"),
+        OwnerTierSummaryResponse {
+            owner: "alice".to_string(),
+            tiers: tier_counts(&[("bronze", 3)]),
+            highest_tier: Some("bronze".to_string()),
+        }
+    );
+
+    let upgrade = ExecuteMsg::UpgradeTierSigned {
+        token_id: "contribution-1".to_string(),
+        points: Uint128::new(10_000),
+        nonce: 5,
+        expires,
+        signature: sign_attestation(&key, "alice", 10_000, 5, expires),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), upgrade).unwrap();
+    assert_eq!(
+        tokens_by_tier(&deps, "bronze", None, None),
+        vec!["contribution-2", "contribution-3", "contribution-4"]
+    );
+    assert_eq!(tokens_by_tier(&deps, "gold", None, None), vec!["contribution-1"]);
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Burn { token_id: "contribution-2".to_string() },
+    )
+    .unwrap();
+    assert_eq!(tokens_by_tier(&deps, "bronze", None, None), vec!["contribution-3", "contribution-4"]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::TierCounts {}).unwrap();
+    assert_eq!(
+        from_binary::<TierCountsResponse>(&res).unwrap().tiers,
+        tier_counts(&[("bronze", 2), ("gold", 1), ("silver", 1)])
+    );
+    assert_eq!(
+        owner_tier_summary(&deps, "alice"),
+        OwnerTierSummaryResponse {
+            owner: "alice".to_string(),
+            tiers: tier_counts(&[("gold", 1), ("bronze", 1)]),
+            highest_tier: Some("gold".to_string()),
+        }
+    );
+}
+
+// Four-leaf tree hashed with sorted pairs, built off-chain from alice:100, bob:1000, carol:10000 and dave:100
+const FIXTURE_ROOT: &str = "ad84621bcd9196071bd02cb861db070cf501eb8b184e9e94299adc6331e43b8d";
+
+fn fixture_proof(address: &str) -> Vec<String> {
+    let proof: [&str; 2] = match address {
+        "alice" => [
+            "c405c734f0848f0962d596bb3bfdf5fe553d8cb1fd159431ccba6c6aef7eefc3",
+            "9d68bf61f5c56caefa9102f27eb22f5ac6e95e0b87a743c3ce9637e031bcf42a",
+        ],
+        "bob" => [
+            "1eacc66267e16a715ccc56dc6ccab5af5bc37788bf2071001b6713488a6441c8",
+            "9d68bf61f5c56caefa9102f27eb22f5ac6e95e0b87a743c3ce9637e031bcf42a",
+        ],
+        "carol" => [
+            "35e328fbd6b9bfaac5aa98e87b36f90e79e86c4c27c79a769f5e708e84d8093a",
+            "93a563469846741105e6aeaf9f453e6cc84994cb1cda9686e8c18f72d80b0e7d",
+        ],
+        _ => panic!("{} is not in the fixture", address),
+    };
+    proof.iter().map(|node| node.to_string()).collect()
+}
+
+fn try_mint_contribution(
+    deps: &mut TestDeps,
+    sender: &str,
+    proof: Vec<String>,
+    points: u128,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::MintContribution { proof, contribution_points: Uint128::new(points) },
+    )
+}
+
+#[test]
+fn contribution_proofs_verify_against_a_sorted_pair_tree() {
+    let mut deps = setup();
+    set_root(&mut deps, FIXTURE_ROOT.to_string());
+
+    let alice = mint_contribution(&mut deps, "alice", fixture_proof("alice"), 100);
+    let carol = mint_contribution(&mut deps, "carol", fixture_proof("carol"), 10_000);
+    let tier = |deps: &TestDeps, token_id: &str| {
+        Cw721Contract::<ContractExtension, Empty>::default()
+            .tokens
+            .load(&deps.storage, token_id)
+            .unwrap()
+            .extension
+            .tier
+    };
+    assert_eq!(tier(&deps, &alice).as_deref(), Some("bronze"));
+    assert_eq!(tier(&deps, &carol).as_deref(), Some("gold"));
+
+    // Points other than the allocation, or someone else's proof, do not hash to the root
+    let err = try_mint_contribution(&mut deps, "bob", fixture_proof("bob"), 10_000).unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {});
+    let err = try_mint_contribution(&mut deps, "bob", fixture_proof("alice"), 1_000).unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {});
+    let err = try_mint_contribution(&mut deps, "dave", fixture_proof("alice"), 100).unwrap_err();
+    assert_eq!(err, ContractError::InvalidProof {});
+    mint_contribution(&mut deps, "bob", fixture_proof("bob"), 1_000);
+}
+
+#[test]
+fn each_allocation_mints_once() {
+    let mut deps = setup();
+    set_root(&mut deps, FIXTURE_ROOT.to_string());
+    let token_id = mint_contribution(&mut deps, "alice", fixture_proof("alice"), 100);
+
+    let err = try_mint_contribution(&mut deps, "alice", fixture_proof("alice"), 100).unwrap_err();
+    assert_eq!(err, ContractError::ProofAlreadyUsed {});
+
+    // Neither giving the token away nor burning it frees the allocation
+    transfer(&mut deps, "alice", "bob", &token_id);
+    let err = try_mint_contribution(&mut deps, "alice", fixture_proof("alice"), 100).unwrap_err();
+    assert_eq!(err, ContractError::ProofAlreadyUsed {});
+    execute(deps.as_mut(), mock_env(), mock_info("bob", &[]), ExecuteMsg::Burn { token_id }).unwrap();
+    let err = try_mint_contribution(&mut deps, "alice", fixture_proof("alice"), 100).unwrap_err();
+    assert_eq!(err, ContractError::ProofAlreadyUsed {});
+}
+
+#[test]
+fn only_the_claimant_can_upgrade_a_contribution_token() {
+    let mut deps = setup();
+    let (root, alice_proof, bob_proof) = merkle_pair(leaf("alice", 100), leaf("bob", 100));
+    set_root(&mut deps, root);
+    let alice_token = mint_contribution(&mut deps, "alice", alice_proof, 100);
+    let bob_token = mint_contribution(&mut deps, "bob", bob_proof, 100);
+    transfer(&mut deps, "bob", "alice", &bob_token);
+
+    let (root, alice_proof, _) = merkle_pair(leaf("alice", 10_000), leaf("bob", 100));
+    set_root(&mut deps, root);
+    let upgrade = |token_id: &str| ExecuteMsg::UpgradeTier {
+        token_id: token_id.to_string(),
+        proof: alice_proof.clone(),
+        new_points: Uint128::new(10_000),
+    };
+
+    // Alice owns Bob's token now, but her allocation is not the one it was minted from
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), upgrade(&bob_token)).unwrap_err();
+    assert_eq!(err, ContractError::NotClaimant { token_id: bob_token });
+
+    execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), upgrade(&alice_token)).unwrap();
+    let token = Cw721Contract::<ContractExtension, Empty>::default()
+        .tokens
+        .load(&deps.storage, &alice_token)
+        .unwrap();
+    assert_eq!(token.extension.tier.as_deref(), Some("gold"));
+    assert_eq!(voting_power(&deps, "alice", None), (2, 2));
+}
+
+fn env_at(seconds: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(seconds);
+    env
+}
+
+fn priced_phases(presale_price: u128) -> Vec<MintPhase> {
+    let now = mock_env().block.time;
+    vec![
+        MintPhase {
+            name: "presale".to_string(),
+            kind: PhaseKind::Public {},
+            start: now,
+            end: now.plus_seconds(100),
+            price: Some(Uint128::new(presale_price)),
+            max_per_address: None,
+        },
+        MintPhase {
+            name: "public".to_string(),
+            kind: PhaseKind::Public {},
+            start: now.plus_seconds(100),
+            end: now.plus_seconds(1_000),
+            price: None,
+            max_per_address: None,
+        },
+    ]
+}
+
+fn allowlist_mint(
+    deps: &mut TestDeps,
+    env: Env,
+    sender: &str,
+    count: u32,
+    proof: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(sender, &coins(500_000 * u128::from(count), DENOM)),
+        ExecuteMsg::MintCurveBatch {
+            count,
+            max_total_price: Uint128::new(500_000 * u128::from(count)),
+            allowlist_proof: proof,
+        },
+    )
+}
+
+#[test]
+fn allowlist_phases_check_the_proof_and_the_per_address_limit() {
+    let (root, alice_proof, bob_proof) = merkle_pair(sha256(b"alice"), sha256(b"bob"));
+    let now = mock_env().block.time;
+    let mut deps = setup_with(InstantiateMsg {
+        phases: Some(vec![
+            MintPhase {
+                name: "allowlist".to_string(),
+                kind: PhaseKind::Allowlist { merkle_root: root },
+                start: now,
+                end: now.plus_seconds(100),
+                price: Some(Uint128::new(500_000)),
+                max_per_address: Some(2),
+            },
+            MintPhase {
+                name: "public".to_string(),
+                kind: PhaseKind::Public {},
+                start: now.plus_seconds(100),
+                end: now.plus_seconds(200),
+                price: Some(Uint128::new(500_000)),
+                max_per_address: None,
+            },
+        ]),
+        ..instantiate_msg()
+    });
+
+    // Only a proof of the sender's own leaf gets in
+    let wrong_proofs = [("carol", None), ("carol", Some(alice_proof.clone())), ("alice", Some(bob_proof))];
+    for (sender, proof) in wrong_proofs {
+        let err = allowlist_mint(&mut deps, mock_env(), sender, 1, proof).unwrap_err();
+        assert_eq!(err, ContractError::NotAllowlisted {});
+    }
+    let err = allowlist_mint(&mut deps, mock_env(), "alice", 1, Some(vec!["zz".to_string()])).unwrap_err();
+    assert_eq!(err, ContractError::NotAllowlisted {});
+
+    allowlist_mint(&mut deps, mock_env(), "alice", 1, Some(alice_proof.clone())).unwrap();
+    let limit = ContractError::PhaseLimitReached { phase: "allowlist".to_string(), max_per_address: 2 };
+    let err = allowlist_mint(&mut deps, mock_env(), "alice", 2, Some(alice_proof.clone())).unwrap_err();
+    assert_eq!(err, limit);
+    allowlist_mint(&mut deps, mock_env(), "alice", 1, Some(alice_proof.clone())).unwrap();
+    let err = allowlist_mint(&mut deps, mock_env(), "alice", 1, Some(alice_proof)).unwrap_err();
+    assert_eq!(err, limit);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::GetPhaseMints { phase: "allowlist".to_string(), address: "alice".to_string() },
+    )
+    .unwrap();
+    assert_eq!(from_binary::<PhaseMintsResponse>(&res).unwrap().minted, 2);
+
+    // The public phase needs no proof and counts from zero, nothing mints once the phases are over
+    allowlist_mint(&mut deps, env_at(100), "alice", 3, None).unwrap();
+    let err = allowlist_mint(&mut deps, env_at(200), "alice", 1, None).unwrap_err();
+    assert_eq!(err, ContractError::MintingClosed {});
+}
+
+#[test]
+fn sell_backs_are_capped_at_the_reserve() {
+    let mut deps = setup_with(InstantiateMsg {
+        phases: Some(priced_phases(100_000)),
+        ..instantiate_msg()
+    });
+    let cheap = mint_curve(&mut deps, "alice", 100_000);
+    execute(
+        deps.as_mut(),
+        env_at(200),
+        mock_info("bob", &coins(1_001_000, DENOM)),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+    .unwrap();
+    assert_eq!(backing(&deps, &cheap), Uint128::new(100_000));
+    assert_eq!(reserve(&deps), Uint128::new(1_000_900));
+
+    let sell = |deps: &mut TestDeps, seller: &str, token_id: String| {
+        execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info(seller, &[]),
+            ExecuteMsg::SellCurve { token_id },
+        )
+        .unwrap()
+        .messages[0]
+            .msg
+            .clone()
+    };
+    let paid = |seller: &str, amount: u128| {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: seller.to_string(),
+            amount: coins(amount, DENOM),
+        })
+    };
+    assert_eq!(sell(&mut deps, "alice", cheap), paid("alice", 900_900));
+    // The discount left the reserve short of the bottom slot's sell price
+    assert_eq!(sell(&mut deps, "bob", "curve-2".to_string()), paid("bob", 100_000));
+    assert_eq!(reserve(&deps), Uint128::zero());
+}
+
+#[test]
+fn free_phase_mints_cannot_be_sold_back() {
+    let mut deps = setup_with(InstantiateMsg {
+        phases: Some(priced_phases(0)),
+        ..instantiate_msg()
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+    .unwrap();
+    let token_id = res.attributes.iter().find(|attr| attr.key == "token_id").unwrap().value.clone();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::SellCurve { token_id: token_id.clone() },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotBacked { token_id });
+}
+
+fn auction(rebate: bool) -> SaleMode {
+    SaleMode::DutchAuction(DutchAuctionConfig {
+        start_price: Uint128::new(2_000_000),
+        floor_price: Uint128::new(1_000_000),
+        start_time: mock_env().block.time,
+        step_seconds: 60,
+        step_decrease: Uint128::new(100_000),
+        rebate,
+    })
+}
+
+fn settle_auction(deps: &mut TestDeps) {
+    execute(deps.as_mut(), mock_env(), mock_info(ADMIN_ADDR, &[]), ExecuteMsg::SettleAuction {}).unwrap();
+}
+
+#[test]
+fn auction_mints_stay_unsellable_after_returning_to_the_curve() {
+    let mut deps = setup_with(InstantiateMsg {
+        sale_mode: Some(auction(false)),
+        ..instantiate_msg()
+    });
+    let token_id = mint_curve(&mut deps, "alice", 2_000_000);
+    settle_auction(&mut deps);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::UpdateConfig(ConfigUpdate {
+            sale_mode: Some(SaleMode::BondingCurve {}),
+            ..ConfigUpdate::default()
+        }),
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::SellCurve { token_id: token_id.clone() },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotBacked { token_id });
+}
+
+#[test]
+fn rebate_below_the_clearing_price_is_nothing_to_claim() {
+    let mut deps = setup_with(InstantiateMsg {
+        sale_mode: Some(auction(true)),
+        ..instantiate_msg()
+    });
+    mint_curve(&mut deps, "alice", 2_000_000);
+    settle_auction(&mut deps);
+    let auction_id = AUCTION_ID.load(&deps.storage).unwrap();
+    // A purchase recorded below the clearing price
+    AUCTION_PURCHASES
+        .save(
+            deps.as_mut().storage,
+            (auction_id, &Addr::unchecked("bob")),
+            &AuctionPurchase {
+                count: 1,
+                paid: Uint128::new(1_000_000),
+            },
+        )
+        .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::GetRebate {
+            auction_id,
+            address: "bob".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(from_binary::<RebateResponse>(&res).unwrap().rebate, Some(Uint128::zero()));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::ClaimRebate { auction_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NothingToClaim {});
+}
+
+#[test]
+fn burn_with_a_drifted_counter_is_an_error() {
+    let mut deps = setup();
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    CURVE_MINTED.save(deps.as_mut().storage, &0).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Burn { token_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MintedCountUnderflow {});
+}
+
+#[test]
+fn cw721_messages_convert_and_the_rest_are_rejected() {
+    let msg: cw721_base::ExecuteMsg<ContractExtension, Empty> =
+        ExecuteMsg::Extension { msg: Empty {} }.try_into().unwrap();
+    assert_eq!(msg, cw721_base::ExecuteMsg::Extension { msg: Empty {} });
+    let msg: cw721_base::ExecuteMsg<ContractExtension, Empty> =
+        mint_msg("album-1", "alice").try_into().unwrap();
+    assert_eq!(
+        msg,
+        cw721_base::ExecuteMsg::Mint {
+            token_id: "album-1".to_string(),
+            owner: "alice".to_string(),
+            token_uri: None,
+            extension: ContractExtension::default(),
+        }
+    );
+    let err =
+        cw721_base::ExecuteMsg::<ContractExtension, Empty>::try_from(ExecuteMsg::Withdraw {}).unwrap_err();
+    assert_eq!(err, ContractError::NotCw721Message {});
+
+    let msg: cw721_base::QueryMsg<ContractExtension> = QueryMsg::AllNftInfo {
+        token_id: "album-1".to_string(),
+        include_expired: None,
+    }
+    .try_into()
+    .unwrap();
+    assert_eq!(
+        msg,
+        cw721_base::QueryMsg::AllNftInfo { token_id: "album-1".to_string(), include_expired: None }
+    );
+    let err = cw721_base::QueryMsg::<ContractExtension>::try_from(QueryMsg::GetReserve {}).unwrap_err();
+    assert_eq!(err, ContractError::NotCw721Message {});
+
+    // Unknown extension messages are accepted and do nothing, as in cw721-base
+    let mut deps = setup();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Extension { msg: Empty {} },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+}
+
+fn update_ownership(
+    deps: &mut TestDeps,
+    sender: &str,
+    action: cw721_base::Action,
+) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), ExecuteMsg::UpdateOwnership(action))
+}
+
+#[test]
+fn the_minter_handed_over_by_update_ownership_can_mint() {
+    let mut deps = setup();
+    update_ownership(
+        &mut deps,
+        ADMIN_ADDR,
+        cw721_base::Action::TransferOwnership { new_owner: "label".to_string(), expiry: None },
+    )
+    .unwrap();
+
+    // Until accepted the handover is pending and the old minter keeps minting
+    let err = execute(deps.as_mut(), mock_env(), mock_info("label", &[]), mint_msg("album-1", "alice"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Cw721Error(_)));
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    assert_eq!(
+        from_binary::<cw721_base::Ownership<Addr>>(&res).unwrap(),
+        cw721_base::Ownership {
+            owner: Some(Addr::unchecked(ADMIN_ADDR)),
+            pending_owner: Some(Addr::unchecked("label")),
+            pending_expiry: None,
+        }
+    );
+
+    update_ownership(&mut deps, "label", cw721_base::Action::AcceptOwnership).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap();
+    assert_eq!(
+        from_binary::<cw721_base::MinterResponse>(&res).unwrap().minter.as_deref(),
+        Some("label")
+    );
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info(ADMIN_ADDR, &[]), mint_msg("album-1", "alice"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Cw721Error(_)));
+    execute(deps.as_mut(), mock_env(), mock_info("label", &[]), mint_msg("album-1", "alice")).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap();
+    assert_eq!(from_binary::<NumTokensResponse>(&res).unwrap().count, 1);
+}
+
+#[test]
+fn nft_info_queries_answer_in_cw721_base_json() {
+    let mut deps = setup();
+    let extension = ContractExtension { tier: Some("gold".to_string()), ..ContractExtension::default() };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::Mint {
+            token_id: "album-1".to_string(),
+            owner: "alice".to_string(),
+            token_uri: Some("ipfs://album-1".to_string()),
+            extension: Some(extension.clone()),
+        },
+    )
+    .unwrap();
+    let info = NftInfoResponse { token_uri: Some("ipfs://album-1".to_string()), extension };
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NftInfo { token_id: "album-1".to_string() },
+    )
+    .unwrap();
+    assert_eq!(res, to_binary(&info).unwrap());
+    assert!(String::from_utf8(res.to_vec())
+        .unwrap()
+        .starts_with(r#"{"token_uri":"ipfs://album-1","extension":{"#));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AllNftInfo { token_id: "album-1".to_string(), include_expired: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        to_binary(&AllNftInfoResponse {
+            access: OwnerOfResponse { owner: "alice".to_string(), approvals: vec![] },
+            info,
+        })
+        .unwrap()
+    );
+    assert!(String::from_utf8(res.to_vec())
+        .unwrap()
+        .starts_with(r#"{"access":{"owner":"alice","approvals":[]},"info":{"token_uri":"ipfs://album-1""#));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    assert_eq!(
+        String::from_utf8(res.to_vec()).unwrap(),
+        r#"{"owner":"admin","pending_owner":null,"pending_expiry":null}"#
+    );
+}
+
+fn attributes(attributes: &[Attribute], key: &str) -> Vec<String> {
+    attributes.iter().filter(|attr| attr.key == key).map(|attr| attr.value.clone()).collect()
+}
+
+fn digm_events(res: &Response, ty: &str) -> Vec<Vec<Attribute>> {
+    res.events.iter().filter(|event| event.ty == ty).map(|event| event.attributes.clone()).collect()
+}
+
+#[test]
+fn mints_burns_and_sell_backs_keep_cw721_attributes_and_emit_events() {
+    let mut deps = setup();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1_000_000, DENOM)),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+    .unwrap();
+    assert_eq!(attributes(&res.attributes, "action"), vec!["mint"]);
+    let mints = digm_events(&res, "digm_mint");
+    assert_eq!(mints.len(), 1);
+    assert_eq!(attributes(&mints[0], "kind"), vec!["curve"]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &coins(2_003_000, DENOM)),
+        ExecuteMsg::MintCurveBatch {
+            count: 2,
+            max_total_price: Uint128::new(2_003_000),
+            allowlist_proof: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(attributes(&res.attributes, "action"), vec!["mint", "mint"]);
+    assert_eq!(digm_events(&res, "digm_mint").len(), 2);
+
+    // Any curve token sells for the top slot's price less the spread
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::SellCurve { token_id: "curve-1".to_string() },
+    )
+    .unwrap();
+    assert_eq!(attributes(&res.attributes, "action"), vec!["burn"]);
+    let burns = digm_events(&res, "digm_burn");
+    assert_eq!(burns.len(), 1);
+    assert_eq!(
+        burns[0],
+        vec![
+            Attribute::new("schema_version", EVENT_SCHEMA_VERSION),
+            Attribute::new("token_id", "curve-1"),
+            Attribute::new("owner", "alice"),
+            Attribute::new("kind", "sell"),
+            Attribute::new("payout", "901800"),
+            Attribute::new("denom", DENOM),
+        ]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::Burn { token_id: "curve-2".to_string() },
+    )
+    .unwrap();
+    assert_eq!(attributes(&res.attributes, "action"), vec!["burn"]);
+    assert_eq!(
+        digm_events(&res, "digm_burn"),
+        vec![vec![
+            Attribute::new("schema_version", EVENT_SCHEMA_VERSION),
+            Attribute::new("token_id", "curve-2"),
+            Attribute::new("owner", "bob"),
+            Attribute::new("kind", "burn"),
+        ]]
+    );
+}
+
+fn send(deps: &mut TestDeps, sender: &str, contract: &str, token_id: &str) -> Response {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::SendNft {
+            contract: contract.to_string(),
+            token_id: token_id.to_string(),
+            msg: to_binary("list").unwrap(),
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn send_nft_calls_the_receiving_contract() {
+    let mut deps = setup();
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+
+    let res = send(&mut deps, "alice", "market", &token_id);
+    assert_eq!(res.messages.len(), 1);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => assert_eq!(contract_addr, "market"),
+        msg => panic!("unexpected message {:?}", msg),
+    }
+}
+
+#[test]
+fn tokens_made_soulbound_in_escrow_go_back_to_their_sender() {
+    let mut deps = setup();
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    send(&mut deps, "alice", "market", &token_id);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::SetSoulbound { token_id: token_id.clone(), soulbound: true },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("market", &[]),
+        ExecuteMsg::TransferNft { recipient: "bob".to_string(), token_id: token_id.clone() },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Soulbound { token_id: token_id.clone() });
+
+    transfer(&mut deps, "market", "alice", &token_id);
+
+    // Back home the token is bound again
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::TransferNft { recipient: "market".to_string(), token_id: token_id.clone() },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Soulbound { token_id });
+}
+
+fn entitled(deps: &TestDeps, owner: &str, album_id: &str) -> bool {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::HasEntitlement {
+            owner: owner.to_string(),
+            album_id: album_id.to_string(),
+        },
+    )
+    .unwrap();
+    from_binary::<HasEntitlementResponse>(&res).unwrap().entitled
+}
+
+fn set_user(
+    deps: &mut TestDeps,
+    sender: &str,
+    token_id: &str,
+    user: Option<&str>,
+    expires: u64,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::SetUser {
+            token_id: token_id.to_string(),
+            user: user.map(str::to_string),
+            expires,
+        },
+    )
+}
+
+fn user_of(deps: &TestDeps, env: Env, token_id: &str) -> UserOfResponse {
+    let res = query(deps.as_ref(), env, QueryMsg::UserOf { token_id: token_id.to_string() }).unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn users_are_set_by_those_who_may_transfer_and_expire_or_go_with_a_transfer() {
+    let mut deps = setup_with(InstantiateMsg { album: Some(album("album-1")), ..instantiate_msg() });
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    let expires = mock_env().block.time.seconds() + 100;
+    let none = UserOfResponse { user: None, expires: None };
+
+    assert_eq!(
+        set_user(&mut deps, "bob", &token_id, Some("bob"), expires).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+    assert_eq!(
+        set_user(&mut deps, "alice", &token_id, Some("bob"), mock_env().block.time.seconds()).unwrap_err(),
+        ContractError::RentalExpired {}
+    );
+
+    set_user(&mut deps, "alice", &token_id, Some("bob"), expires).unwrap();
+    assert_eq!(
+        user_of(&deps, mock_env(), &token_id),
+        UserOfResponse { user: Some("bob".to_string()), expires: Some(expires) }
+    );
+    assert!(entitled(&deps, "bob", "album-1"));
+    assert_eq!(user_of(&deps, env_at(100), &token_id), none);
+
+    // Approved spenders and operators may lend the token as well
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::Approve { spender: "carol".to_string(), token_id: token_id.clone(), expires: None },
+    )
+    .unwrap();
+    set_user(&mut deps, "carol", &token_id, Some("carol"), expires).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::ApproveAll { operator: "dave".to_string(), expires: None },
+    )
+    .unwrap();
+    set_user(&mut deps, "dave", &token_id, Some("dave"), expires).unwrap();
+    assert_eq!(user_of(&deps, mock_env(), &token_id).user.as_deref(), Some("dave"));
+
+    // The rental does not survive a change of owner
+    transfer(&mut deps, "alice", "erin", &token_id);
+    assert_eq!(user_of(&deps, mock_env(), &token_id), none);
+    assert!(!entitled(&deps, "dave", "album-1"));
+    assert_eq!(
+        set_user(&mut deps, "dave", &token_id, Some("dave"), expires).unwrap_err(),
+        ContractError::Unauthorized {}
+    );
+
+    set_user(&mut deps, "erin", &token_id, Some("bob"), expires).unwrap();
+    set_user(&mut deps, "erin", &token_id, None, 0).unwrap();
+    assert_eq!(user_of(&deps, mock_env(), &token_id), none);
+}
+
+fn set_license_scope(deps: &mut TestDeps, token_id: &str, album_id: Option<&str>) {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::SetLicenseScope {
+            token_id: token_id.to_string(),
+            album_id: album_id.map(str::to_string),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn clearing_the_license_scope_revokes_the_album() {
+    let mut deps = setup_with(InstantiateMsg { album: Some(album("album-1")), ..instantiate_msg() });
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    assert!(entitled(&deps, "alice", "album-1"));
+
+    // The token still carries the album it was minted with
+    set_license_scope(&mut deps, &token_id, None);
+    assert!(!entitled(&deps, "alice", "album-1"));
+
+    set_license_scope(&mut deps, &token_id, Some("album-2"));
+    assert!(!entitled(&deps, "alice", "album-1"));
+    assert!(entitled(&deps, "alice", "album-2"));
+}
+
+fn album(album_id: &str) -> AlbumMetadata {
+    AlbumMetadata {
+        album_id: album_id.to_string(),
+        title: "Album".to_string(),
+        tracks: vec![TrackMetadata {
+            title: "Track".to_string(),
+            duration_secs: 180,
+            content_hash: "ab".repeat(32),
+        }],
+        content_root: "cd".repeat(32),
+        cover_hash: String::new(),
+        artist: "artist".to_string(),
+        license_terms: String::new(),
+    }
+}
+
+fn update_metadata(
+    deps: &mut TestDeps,
+    sender: &str,
+    token_id: &str,
+    album: AlbumMetadata,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(sender, &[]),
+        ExecuteMsg::UpdateMetadata { token_id: token_id.to_string(), album },
+    )
+}
+
+fn freeze_metadata(deps: &mut TestDeps, sender: &str) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), ExecuteMsg::FreezeMetadata {})
+}
+
+#[test]
+fn metadata_updates_until_frozen_for_good() {
+    let mut deps = setup_with(InstantiateMsg { album: Some(album("album-1")), ..instantiate_msg() });
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+    let remastered = AlbumMetadata { title: "Remastered".to_string(), ..album("album-1") };
+
+    // The album's artist and the admin may edit, not the holder, and only with valid metadata
+    let err = update_metadata(&mut deps, "alice", &token_id, remastered.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let untracked = AlbumMetadata { tracks: vec![], ..album("album-1") };
+    let err = update_metadata(&mut deps, "artist", &token_id, untracked).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidMetadata { .. }));
+    update_metadata(&mut deps, "artist", &token_id, remastered.clone()).unwrap();
+    update_metadata(&mut deps, ADMIN_ADDR, &token_id, remastered.clone()).unwrap();
+    let token = Cw721Contract::<ContractExtension, Empty>::default()
+        .tokens
+        .load(&deps.storage, &token_id)
+        .unwrap();
+    assert_eq!(token.extension.album, Some(remastered.clone()));
+
+    assert!(matches!(freeze_metadata(&mut deps, "artist").unwrap_err(), ContractError::Admin(_)));
+    freeze_metadata(&mut deps, ADMIN_ADDR).unwrap();
+
+    assert_eq!(
+        update_metadata(&mut deps, "artist", &token_id, album("album-1")).unwrap_err(),
+        ContractError::MetadataFrozen {}
+    );
+    assert_eq!(
+        update_metadata(&mut deps, ADMIN_ADDR, &token_id, album("album-1")).unwrap_err(),
+        ContractError::MetadataFrozen {}
+    );
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::UpdateConfig(ConfigUpdate {
+            base_uri: Some("ipfs://new/{token_id}".to_string()),
+            ..ConfigUpdate::default()
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MetadataFrozen {});
+
+    // Nothing thaws it, freezing again included
+    freeze_metadata(&mut deps, ADMIN_ADDR).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetMetadataConfig {}).unwrap();
+    let config: MetadataConfigResponse = from_binary(&res).unwrap();
+    assert!(config.frozen);
+    assert_eq!(config.album, Some(album("album-1")));
+}
+
+#[test]
+fn platform_fee_above_the_denominator_is_rejected() {
+    let mut deps = mock_dependencies();
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ADMIN_ADDR, &[]),
+        InstantiateMsg {
+            platform_fee: Some(PlatformFee {
+                recipient: "treasury".to_string(),
+                bps: 10_001,
+            }),
+            ..instantiate_msg()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPlatformFee { bps: 10_001 });
+}
+
+fn payees(weights: &[(&str, u64)]) -> Option<Vec<Payee>> {
+    Some(
+        weights
+            .iter()
+            .map(|(address, weight)| Payee { address: address.to_string(), weight: *weight })
+            .collect(),
+    )
+}
+
+#[test]
+fn payee_weights_must_sum_within_bounds() {
+    for (weights, reason) in [
+        (vec![("alice", u64::MAX), ("bob", 1)], "total weight overflows"),
+        (vec![("alice", 1), ("bob", 0)], "bob has zero weight"),
+    ] {
+        let mut deps = mock_dependencies();
+        let err = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN_ADDR, &[]),
+            InstantiateMsg { payees: payees(&weights), ..instantiate_msg() },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPayees { reason: reason.to_string() });
+    }
+
+    // The largest weights that still fit split revenue without overflowing
+    let mut deps = setup_with(InstantiateMsg {
+        payees: payees(&[("alice", u64::MAX / 2), ("bob", u64::MAX / 2)]),
+        ..instantiate_msg()
+    });
+    mint_curve(&mut deps, "carol", 1_000_000);
+    assert_eq!(claimable(&deps, "alice"), coins(50_000, DENOM));
+    assert_eq!(claimable(&deps, "bob"), coins(50_000, DENOM));
+}
+
+#[test]
+fn revenue_splits_by_weight_and_is_paid_once() {
+    let mut deps = setup_with(InstantiateMsg {
+        payees: payees(&[("alice", 1), ("bob", 2)]),
+        ..instantiate_msg()
+    });
+    // 100_000 of revenue, the unit lost to rounding goes to the first payee
+    mint_curve(&mut deps, "carol", 1_000_000);
+    assert_eq!(claimable(&deps, "alice"), coins(33_334, DENOM));
+    assert_eq!(claimable(&deps, "bob"), coins(66_666, DENOM));
+    assert_eq!(claimable(&deps, "carol"), vec![]);
+
+    let withdraw = |deps: &mut TestDeps, sender: &str| {
+        execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), ExecuteMsg::Withdraw {})
+    };
+    let res = withdraw(&mut deps, "bob").unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send { to_address: "bob".to_string(), amount: coins(66_666, DENOM) })
+    );
+    assert_eq!(withdraw(&mut deps, "bob").unwrap_err(), ContractError::NothingToClaim {});
+    assert_eq!(withdraw(&mut deps, "carol").unwrap_err(), ContractError::NothingToClaim {});
+
+    // Only what is still owed is distributed, and only once
+    let distribute = |deps: &mut TestDeps| {
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::DistributeProceeds {})
+    };
+    let res = distribute(&mut deps).unwrap();
+    let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|sub| sub.msg).collect();
+    assert_eq!(
+        messages,
+        vec![CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(33_334, DENOM) })]
+    );
+    assert_eq!(claimable(&deps, "alice"), vec![]);
+    assert_eq!(distribute(&mut deps).unwrap_err(), ContractError::NothingToClaim {});
+
+    // Later revenue accrues again from zero
+    mint_curve(&mut deps, "carol", 1_001_000);
+    assert_eq!(claimable(&deps, "alice"), coins(33_367, DENOM));
+    assert_eq!(claimable(&deps, "bob"), coins(66_733, DENOM));
+}
+
+fn env_at_height(blocks: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height += blocks;
+    env
+}
+
+// Alice holds a gold (2) and Bob a silver (1) contribution token
+fn governed(governance: Option<GovernanceConfig>) -> (TestDeps, String, String) {
+    let mut deps = setup_with(InstantiateMsg {
+        governance,
+        ..instantiate_msg()
+    });
+    let (root, alice_proof, bob_proof) = merkle_pair(leaf("alice", 10_000), leaf("bob", 1_000));
+    set_root(&mut deps, root);
+    let alice_token = mint_contribution(&mut deps, "alice", alice_proof, 10_000);
+    let bob_token = mint_contribution(&mut deps, "bob", bob_proof, 1_000);
+    (deps, alice_token, bob_token)
+}
+
+fn propose(deps: &mut TestDeps, env: Env, proposer: &str) -> u64 {
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(proposer, &[]),
+        ExecuteMsg::Propose {
+            title: "Root".to_string(),
+            description: "New contribution round".to_string(),
+            action: ProposalAction::SetContributionRoot { root: hex::encode([7u8; 32]) },
+        },
+    )
+    .unwrap();
+    PROPOSAL_COUNT.load(&deps.storage).unwrap()
+}
+
+fn vote(deps: &mut TestDeps, env: Env, voter: &str, proposal_id: u64) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info(voter, &[]),
+        ExecuteMsg::Vote { proposal_id, vote: VoteOption::Yes {} },
+    )
+}
+
+fn proposal(deps: &TestDeps, proposal_id: u64) -> Proposal {
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposal { proposal_id }).unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn votes_count_the_snapshot_weight_after_transfer_recover_and_burn() {
+    // Only all three weights together clear a 90% threshold
+    let (mut deps, alice_token, bob_token) = governed(Some(GovernanceConfig {
+        threshold_bps: 9000,
+        ..GovernanceConfig::default()
+    }));
+    let proposal_id = propose(&mut deps, env_at_height(1), "alice");
+
+    let mid_vote = env_at_height(2);
+    execute(
+        deps.as_mut(),
+        mid_vote.clone(),
+        mock_info("alice", &[]),
+        ExecuteMsg::TransferNft { recipient: "carol".to_string(), token_id: alice_token.clone() },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mid_vote.clone(),
+        mock_info("carol", &[]),
+        ExecuteMsg::Burn { token_id: alice_token },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mid_vote.clone(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::SetSoulbound { token_id: bob_token.clone(), soulbound: true },
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mid_vote.clone(),
+        mock_info(ADMIN_ADDR, &[]),
+        ExecuteMsg::RecoverToken { token_id: bob_token, recipient: "dave".to_string() },
+    )
+    .unwrap();
+    assert_eq!(voting_power(&deps, "alice", None), (0, 1));
+    assert_eq!(voting_power(&deps, "dave", None), (1, 1));
+
+    // Tokens received after the snapshot carry no vote on it
+    let err = vote(&mut deps, mid_vote.clone(), "dave", proposal_id).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {});
+
+    vote(&mut deps, mid_vote.clone(), "alice", proposal_id).unwrap();
+    let open = proposal(&deps, proposal_id);
+    assert_eq!((open.yes, open.total_weight, open.status), (2, 3, ProposalStatus::Open {}));
+
+    vote(&mut deps, mid_vote, "bob", proposal_id).unwrap();
+    let executed = proposal(&deps, proposal_id);
+    assert_eq!((executed.yes, executed.status), (3, ProposalStatus::Executed {}));
+}
+
+#[test]
+fn decided_proposals_execute_before_voting_ends() {
+    let (mut deps, _, _) = governed(None);
+    let proposal_id = propose(&mut deps, env_at_height(1), "bob");
+
+    // A third of the weight reaches quorum, but not the threshold were the rest to vote no
+    vote(&mut deps, env_at_height(1), "bob", proposal_id).unwrap();
+    assert!(!proposal(&deps, proposal_id).decided());
+    assert_eq!(proposal(&deps, proposal_id).status, ProposalStatus::Open {});
+
+    let res = vote(&mut deps, env_at_height(1), "alice", proposal_id).unwrap();
+    assert!(res.attributes.iter().any(|attr| attr.key == "status" && attr.value == "executed"));
+    assert_eq!(proposal(&deps, proposal_id).status, ProposalStatus::Executed {});
+    assert_eq!(CONTRIBUTION_ROOT.load(&deps.storage).unwrap(), hex::encode([7u8; 32]));
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(DEFAULT_VOTING_PERIOD),
+        mock_info("bob", &[]),
+        ExecuteMsg::CloseProposal { proposal_id },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ProposalClosed { proposal_id });
+}
+
+// Curve prices quoted in usd and paid in ujuno at `rate`
+fn priced_in_usd(curve_start_price: Uint128, rate: Decimal) -> TestDeps {
+    let mut deps = setup_with(InstantiateMsg {
+        curve_start_price,
+        pricing: Some(PricingConfig {
+            oracle: "oracle".to_string(),
+            base_unit: Some("usd".to_string()),
+            denoms: vec![],
+            max_staleness: 60,
+            max_deviation_bps: 10_000,
+        }),
+        ..instantiate_msg()
+    });
+    set_oracle_rate(&mut deps, rate);
+    deps
+}
+
+fn set_oracle_rate(deps: &mut TestDeps, rate: Decimal) {
+    set_oracle_rate_at(deps, rate, 0);
+}
+
+// The oracle answers `rate` as updated `seconds` after the mock block time
+fn set_oracle_rate_at(deps: &mut TestDeps, rate: Decimal, seconds: u64) {
+    deps.querier.update_wasm(move |_| {
+        let res = ExchangeRateResponse {
+            rate,
+            updated_at: env_at(seconds).block.time,
+        };
+        SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+    });
+}
+
+fn mint_at(deps: &mut TestDeps, env: Env, amount: u128) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env,
+        mock_info("alice", &coins(amount, DENOM)),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+}
+
+#[test]
+fn stale_oracle_rates_are_rejected() {
+    let mut deps = priced_in_usd(Uint128::new(1_000_000), Decimal::one());
+
+    let err = mint_at(&mut deps, env_at(61), 1_000_000).unwrap_err();
+    assert_eq!(err, ContractError::StalePrice { denom: DENOM.to_string(), age: 61 });
+    mint_at(&mut deps, env_at(60), 1_000_000).unwrap();
+}
+
+#[test]
+fn oracle_rates_may_only_move_so_far_from_the_last_one() {
+    let mut deps = setup_with(InstantiateMsg {
+        pricing: Some(PricingConfig {
+            oracle: "oracle".to_string(),
+            base_unit: Some("usd".to_string()),
+            denoms: vec![],
+            max_staleness: 60,
+            max_deviation_bps: 1_000,
+        }),
+        ..instantiate_msg()
+    });
+    set_oracle_rate(&mut deps, Decimal::one());
+    mint_at(&mut deps, mock_env(), 1_000_000).unwrap();
+
+    set_oracle_rate(&mut deps, Decimal::percent(115));
+    let err = mint_at(&mut deps, mock_env(), 2_000_000).unwrap_err();
+    assert_eq!(err, ContractError::PriceDeviation { denom: DENOM.to_string() });
+
+    // A move of exactly the limit is accepted and becomes the new reference
+    set_oracle_rate(&mut deps, Decimal::percent(110));
+    mint_at(&mut deps, mock_env(), 1_101_100).unwrap();
+
+    // Once the reference is older than the staleness window any fresh rate is accepted
+    set_oracle_rate_at(&mut deps, Decimal::percent(200), 61);
+    mint_at(&mut deps, env_at(61), 2_004_000).unwrap();
+}
+
+#[test]
+fn payments_are_taken_in_accepted_denoms_and_overpayments_refunded() {
+    let mut deps = setup_with(InstantiateMsg {
+        pricing: Some(PricingConfig {
+            oracle: "oracle".to_string(),
+            base_unit: None,
+            denoms: vec!["uatom".to_string()],
+            max_staleness: 60,
+            max_deviation_bps: 10_000,
+        }),
+        ..instantiate_msg()
+    });
+    set_oracle_rate(&mut deps, Decimal::percent(200));
+    let mint = |deps: &mut TestDeps, funds: &[Coin]| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", funds),
+            ExecuteMsg::MintCurve { allowlist_proof: None },
+        )
+    };
+
+    let err = mint(&mut deps, &coins(1_000_000, "uosmo")).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidDenom {
+            expected: "ujuno,uatom".to_string(),
+            received: "uosmo".to_string(),
+        }
+    );
+    let err = mint(&mut deps, &[coin(1_000_000, DENOM), coin(2_000_000, "uatom")]).unwrap_err();
+    assert_eq!(err, ContractError::MultipleDenoms {});
+
+    // The curve denom is taken as is, anything above the price goes back
+    let res = mint(&mut deps, &coins(1_200_000, DENOM)).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(200_000, DENOM) })
+    );
+    assert_eq!(attributes(&res.attributes, "refund"), vec!["200000"]);
+
+    // Accepted denoms pay the converted price, 1_001_000 at 2 uatom per ujuno
+    let err = mint(&mut deps, &coins(2_001_999, "uatom")).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientPayment { required: Uint128::new(2_002_000) });
+    let res = mint(&mut deps, &coins(2_500_000, "uatom")).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send { to_address: "alice".to_string(), amount: coins(498_000, "uatom") })
+    );
+    // Only the curve denom backs the reserve, the converted payment is all revenue
+    assert_eq!(claimable(&deps, ADMIN_ADDR), vec![coin(2_002_000, "uatom"), coin(100_000, DENOM)]);
+}
+
+#[test]
+fn converted_price_overflow_is_an_error() {
+    let mut deps = priced_in_usd(Uint128::MAX / Uint128::new(2), Decimal::percent(300));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &coins(1, DENOM)),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ConversionOverflow {
+            price: Uint128::MAX / Uint128::new(2),
+            denom: DENOM.to_string(),
+        }
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::QuotePayment { denom: DENOM.to_string(), count: 1 },
+    )
+    .unwrap_err();
+    assert!(matches!(err, StdError::GenericErr { .. }));
+}
+
+#[test]
+fn sell_back_converts_the_curve_price_at_the_current_rate() {
+    let mut deps = priced_in_usd(Uint128::new(1_000_000), Decimal::percent(200));
+    let first = mint_curve(&mut deps, "alice", 2_000_000);
+    let second = mint_curve(&mut deps, "bob", 2_002_000);
+    // 10% sell spread on the ujuno paid
+    assert_eq!(reserve(&deps), Uint128::new(3_601_800));
+
+    let sell = |deps: &mut TestDeps, seller: &str, token_id: String| {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(seller, &[]),
+            ExecuteMsg::SellCurve { token_id },
+        )
+        .unwrap()
+        .messages[0]
+            .msg
+            .clone()
+    };
+
+    // 900_900 usd at one ujuno each
+    set_oracle_rate(&mut deps, Decimal::percent(100));
+    assert_eq!(
+        sell(&mut deps, "alice", first),
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "alice".to_string(),
+            amount: coins(900_900, DENOM),
+        })
+    );
+
+    // 900_000 usd at two ujuno each
+    set_oracle_rate(&mut deps, Decimal::percent(200));
+    assert_eq!(
+        sell(&mut deps, "bob", second),
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "bob".to_string(),
+            amount: coins(1_800_000, DENOM),
+        })
+    );
+    assert_eq!(reserve(&deps), Uint128::new(900_900));
+}
+
+const BRIDGE_ADDR: &str = "bridge";
+
+// Instantiated the way ics721 does it, with cw721-base's message and itself as the minter
+fn voucher_collection() -> TestDeps {
+    let msg = to_binary(&Cw721InstantiateMsg {
+        name: "Origin".to_string(),
+        symbol: "ORIGIN".to_string(),
+        minter: BRIDGE_ADDR.to_string(),
+    })
+    .unwrap();
+    let mut deps = mock_dependencies();
+    instantiate(deps.as_mut(), mock_env(), mock_info(BRIDGE_ADDR, &[]), from_binary(&msg).unwrap()).unwrap();
+    deps
+}
+
+fn set_class_data(deps: &mut TestDeps, default_royalty: Option<Royalty>) -> Result<Response, ContractError> {
+    let class_data = ClassData {
+        name: "Origin".to_string(),
+        symbol: "ORIGIN".to_string(),
+        album: None,
+        default_royalty,
+        base_uri: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(BRIDGE_ADDR, &[]),
+        ExecuteMsg::SetClassData { class_data: to_binary(&class_data).unwrap() },
+    )
+}
+
+#[test]
+fn class_data_only_updates_voucher_collections() {
+    let mut native = setup_with(InstantiateMsg {
+        minter: BRIDGE_ADDR.to_string(),
+        ..instantiate_msg()
+    });
+    let err = set_class_data(&mut native, None).unwrap_err();
+    assert_eq!(err, ContractError::NotVoucherCollection {});
+
+    let mut deps = voucher_collection();
+    let royalty = |bps| Royalty {
+        payment_address: "artist".to_string(),
+        bps,
+    };
+    let err = set_class_data(&mut deps, Some(royalty(10_001))).unwrap_err();
+    assert_eq!(err, ContractError::InvalidRoyalty { bps: 10_001 });
+
+    set_class_data(&mut deps, Some(royalty(500))).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ClassData {}).unwrap();
+    let class_data: ClassData = from_binary(&res).unwrap();
+    assert_eq!(class_data.symbol, "ORIGIN");
+    assert_eq!(class_data.default_royalty, Some(royalty(500)));
+}
+
+fn set_paused(deps: &mut TestDeps, sender: &str, paused: bool) -> Result<Response, ContractError> {
+    let msg = if paused { ExecuteMsg::Pause {} } else { ExecuteMsg::Unpause {} };
+    execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+}
+
+#[test]
+fn pausing_stops_mints_sells_burns_and_transfers() {
+    let mut deps = setup();
+    let (root, alice_proof, _) = merkle_pair(leaf("alice", 100), leaf("bob", 100));
+    set_root(&mut deps, root);
+    let token_id = mint_curve(&mut deps, "alice", 1_000_000);
+
+    assert!(matches!(set_paused(&mut deps, "alice", true).unwrap_err(), ContractError::Admin(_)));
+    set_paused(&mut deps, ADMIN_ADDR, true).unwrap();
+
+    let attempts = [
+        ("alice", coins(1_001_000, DENOM), ExecuteMsg::MintCurve { allowlist_proof: None }),
+        (
+            "alice",
+            coins(1_001_000, DENOM),
+            ExecuteMsg::MintCurveBatch {
+                count: 1,
+                max_total_price: Uint128::new(1_001_000),
+                allowlist_proof: None,
+            },
+        ),
+        (
+            "alice",
+            vec![],
+            ExecuteMsg::MintContribution {
+                proof: alice_proof.clone(),
+                contribution_points: Uint128::new(100),
+            },
+        ),
+        (
+            "alice",
+            vec![],
+            ExecuteMsg::MintContributionSigned {
+                points: Uint128::new(100),
+                nonce: 1,
+                expires: mock_env().block.time.seconds() + 100,
+                signature: Binary::default(),
+            },
+        ),
+        (ADMIN_ADDR, vec![], mint_msg("album-1", "alice")),
+        ("alice", vec![], ExecuteMsg::SellCurve { token_id: token_id.clone() }),
+        ("alice", vec![], ExecuteMsg::Burn { token_id: token_id.clone() }),
+        (
+            "alice",
+            vec![],
+            ExecuteMsg::TransferNft { recipient: "bob".to_string(), token_id: token_id.clone() },
+        ),
+        (
+            "alice",
+            vec![],
+            ExecuteMsg::SendNft {
+                contract: "marketplace".to_string(),
+                token_id: token_id.clone(),
+                msg: Binary::default(),
+            },
+        ),
+    ];
+    for (sender, funds, msg) in attempts {
+        let err = execute(deps.as_mut(), mock_env(), mock_info(sender, &funds), msg).unwrap_err();
+        assert_eq!(err, ContractError::Paused {});
+    }
+
+    set_paused(&mut deps, ADMIN_ADDR, false).unwrap();
+    mint_contribution(&mut deps, "alice", alice_proof, 100);
+    transfer(&mut deps, "alice", "bob", &token_id);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("bob", &[]),
+        ExecuteMsg::SellCurve { token_id },
+    )
+    .unwrap();
+
+    // The bridge can neither mint nor retire vouchers while paused
+    let mut deps = voucher_collection();
+    execute(deps.as_mut(), mock_env(), mock_info(BRIDGE_ADDR, &[]), mint_msg("curve-1", "alice")).unwrap();
+    set_paused(&mut deps, BRIDGE_ADDR, true).unwrap();
+    let err = execute(deps.as_mut(), mock_env(), mock_info(BRIDGE_ADDR, &[]), mint_msg("curve-2", "alice"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(BRIDGE_ADDR, &[]),
+        ExecuteMsg::Burn { token_id: "curve-1".to_string() },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+}
+
+fn mint_msg(token_id: &str, owner: &str) -> ExecuteMsg {
+    ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+    }
+}
+
+#[test]
+fn voucher_collections_keep_origin_ids_and_take_no_native_mints() {
+    let mut deps = voucher_collection();
+    let err = execute(deps.as_mut(), mock_env(), mock_info("alice", &[]), mint_msg("curve-1", "alice"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Cw721Error(_)));
+
+    execute(deps.as_mut(), mock_env(), mock_info(BRIDGE_ADDR, &[]), mint_msg("curve-1", "alice")).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("alice", &[]),
+        ExecuteMsg::MintCurve { allowlist_proof: None },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VoucherCollection {});
+
+    // Native collections keep the curve and contribution ids to their own mints
+    let mut native = setup();
+    let err = execute(native.as_mut(), mock_env(), mock_info(ADMIN_ADDR, &[]), mint_msg("curve-1", "alice"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::ReservedTokenId { token_id: "curve-1".to_string() });
+}
+
+// Stand-in for ics721 with both chains in one app. A token escrowed at home is minted as a voucher
+// from its token data, and a voucher sent back is burned and the escrowed token released
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum BridgeMsg {
+    Connect { origin: String, voucher: String },
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+const BRIDGE_LINK: Item<(Addr, Addr)> = Item::new("link");
+
+fn bridge_instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn bridge_execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: BridgeMsg) -> StdResult<Response> {
+    let Cw721ReceiveMsg { token_id, msg, .. } = match msg {
+        BridgeMsg::Connect { origin, voucher } => {
+            BRIDGE_LINK.save(deps.storage, &(Addr::unchecked(origin), Addr::unchecked(voucher)))?;
+            return Ok(Response::new());
+        }
+        BridgeMsg::ReceiveNft(receive) => receive,
+    };
+    let (origin, voucher) = BRIDGE_LINK.load(deps.storage)?;
+    let receiver: String = from_binary(&msg)?;
+    if info.sender == origin {
+        let data: TokenDataResponse = deps
+            .querier
+            .query_wasm_smart(&origin, &QueryMsg::TokenData { token_id: token_id.clone() })?;
+        let mint = ExecuteMsg::Mint {
+            token_id,
+            owner: receiver,
+            token_uri: data.token_uri,
+            extension: Some(from_binary(&data.token_data)?),
+        };
+        Ok(Response::new().add_message(wasm_execute(voucher, &mint, vec![])?))
+    } else if info.sender == voucher {
+        let burn = ExecuteMsg::Burn { token_id: token_id.clone() };
+        let release = ExecuteMsg::TransferNft { recipient: receiver, token_id };
+        Ok(Response::new()
+            .add_message(wasm_execute(voucher, &burn, vec![])?)
+            .add_message(wasm_execute(origin, &release, vec![])?))
+    } else {
+        Err(StdError::generic_err("unknown collection"))
+    }
+}
+
+fn bridge_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Err(StdError::generic_err("no queries"))
+}
+
+#[test]
+fn contribution_token_round_trips_through_the_bridge() {
+    let mut app = App::default();
+    let nft_code = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let bridge_code =
+        app.store_code(Box::new(ContractWrapper::new(bridge_execute, bridge_instantiate, bridge_query)));
+    let admin = Addr::unchecked(ADMIN_ADDR);
+    let alice = Addr::unchecked("alice");
+
+    let bridge = app
+        .instantiate_contract(bridge_code, admin.clone(), &Empty {}, &[], "ics721", None)
+        .unwrap();
+    let origin = app
+        .instantiate_contract(nft_code, admin.clone(), &instantiate_msg(), &[], "origin", None)
+        .unwrap();
+    let cw721_msg = Cw721InstantiateMsg {
+        name: "Origin".to_string(),
+        symbol: "ORIGIN".to_string(),
+        minter: bridge.to_string(),
+    };
+    let voucher = app
+        .instantiate_contract(nft_code, bridge.clone(), &cw721_msg, &[], "voucher", None)
+        .unwrap();
+    let connect = BridgeMsg::Connect {
+        origin: origin.to_string(),
+        voucher: voucher.to_string(),
+    };
+    app.execute_contract(admin.clone(), bridge.clone(), &connect, &[]).unwrap();
+
+    let root = hex::encode(leaf("alice", 10_000));
+    app.execute_contract(admin, origin.clone(), &ExecuteMsg::SetContributionRoot { root }, &[])
+        .unwrap();
+    let mint = ExecuteMsg::MintContribution {
+        proof: vec![],
+        contribution_points: Uint128::new(10_000),
+    };
+    app.execute_contract(alice.clone(), origin.clone(), &mint, &[]).unwrap();
+    let token_id = "contribution-1".to_string();
+
+    let send_to_bridge = ExecuteMsg::SendNft {
+        contract: bridge.to_string(),
+        token_id: token_id.clone(),
+        msg: to_binary("alice").unwrap(),
+    };
+    let owner_of = |app: &App, collection: &Addr| {
+        app.wrap()
+            .query_wasm_smart::<OwnerOfResponse>(
+                collection,
+                &QueryMsg::OwnerOf { token_id: token_id.clone(), include_expired: None },
+            )
+            .map(|res| res.owner)
+    };
+    let voting_weight = |app: &App, collection: &Addr| {
+        app.wrap()
+            .query_wasm_smart::<VotingPowerResponse>(
+                collection,
+                &QueryMsg::VotingPower { address: "alice".to_string(), height: None },
+            )
+            .unwrap()
+            .weight
+    };
+
+    // Out: escrowed at home, minted under the same id with the same extension on the other side
+    app.execute_contract(alice.clone(), origin.clone(), &send_to_bridge, &[]).unwrap();
+    assert_eq!(owner_of(&app, &origin).unwrap(), bridge.as_str());
+    assert_eq!(owner_of(&app, &voucher).unwrap(), "alice");
+    let info: NftInfoResponse<ContractExtension> = app
+        .wrap()
+        .query_wasm_smart(&voucher, &QueryMsg::NftInfo { token_id: token_id.clone() })
+        .unwrap();
+    assert_eq!(info.extension.tier.as_deref(), Some("gold"));
+    assert_eq!(info.extension.contribution_points, Some(Uint128::new(10_000)));
+    // The origin tier votes at home only
+    assert_eq!(voting_weight(&app, &voucher), 0);
+
+    // Back: the voucher is burned and the escrowed token released to its sender
+    app.execute_contract(alice.clone(), voucher.clone(), &send_to_bridge, &[]).unwrap();
+    assert!(owner_of(&app, &voucher).is_err());
+    let num_tokens: NumTokensResponse =
+        app.wrap().query_wasm_smart(&voucher, &QueryMsg::NumTokens {}).unwrap();
+    assert_eq!(num_tokens.count, 0);
+    assert_eq!(owner_of(&app, &origin).unwrap(), "alice");
+    assert_eq!(voting_weight(&app, &origin), 2);
+}